@@ -0,0 +1,286 @@
+use crate::history::CommandHistory;
+
+/// Cursor-aware text buffer backing the input box. Tracks the cursor as a
+/// char index (not a byte offset, since the buffer is UTF-8). Up/Down
+/// recall is backed by a shared, persisted `CommandHistory` rather than
+/// owning its own entries, so history survives across sessions and restarts.
+pub struct InputEditor {
+    buffer: String,
+    cursor: usize,
+    history_pos: Option<usize>,
+    draft: String,
+}
+
+fn byte_index(s: &str, char_idx: usize) -> usize {
+    s.char_indices()
+        .nth(char_idx)
+        .map(|(b, _)| b)
+        .unwrap_or(s.len())
+}
+
+impl InputEditor {
+    pub fn new() -> Self {
+        Self {
+            buffer: String::new(),
+            cursor: 0,
+            history_pos: None,
+            draft: String::new(),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.buffer
+    }
+
+    pub fn cursor_chars(&self) -> usize {
+        self.cursor
+    }
+
+    /// Clear the buffer without touching history (used by the explicit
+    /// "clear input" action, as opposed to a submitted send).
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.cursor = 0;
+        self.history_pos = None;
+    }
+
+    /// Take the buffer's contents, resetting the cursor. The caller is
+    /// responsible for recording the text into a `CommandHistory`.
+    pub fn submit(&mut self) -> String {
+        let text = std::mem::take(&mut self.buffer);
+        self.cursor = 0;
+        self.history_pos = None;
+        text
+    }
+
+    /// Replace the buffer's contents outright, e.g. accepting a
+    /// reverse-search match. Moves the cursor to the end.
+    pub fn set_text(&mut self, text: String) {
+        self.buffer = text;
+        self.cursor = self.buffer.chars().count();
+        self.history_pos = None;
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        self.history_pos = None;
+        let idx = byte_index(&self.buffer, self.cursor);
+        self.buffer.insert(idx, c);
+        self.cursor += 1;
+    }
+
+    /// Insert a (possibly multi-line) string, e.g. from a bracketed paste.
+    pub fn insert_str(&mut self, s: &str) {
+        self.history_pos = None;
+        let idx = byte_index(&self.buffer, self.cursor);
+        self.buffer.insert_str(idx, s);
+        self.cursor += s.chars().count();
+    }
+
+    pub fn backspace(&mut self) {
+        if self.cursor == 0 {
+            return;
+        }
+        self.history_pos = None;
+        let start = byte_index(&self.buffer, self.cursor - 1);
+        let end = byte_index(&self.buffer, self.cursor);
+        self.buffer.replace_range(start..end, "");
+        self.cursor -= 1;
+    }
+
+    pub fn move_left(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+        }
+    }
+
+    pub fn move_right(&mut self) {
+        if self.cursor < self.buffer.chars().count() {
+            self.cursor += 1;
+        }
+    }
+
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor = self.buffer.chars().count();
+    }
+
+    /// Skip any whitespace under the cursor, then skip to the end of the
+    /// following word.
+    pub fn move_word_right(&mut self) {
+        let chars: Vec<char> = self.buffer.chars().collect();
+        let len = chars.len();
+        let mut i = self.cursor;
+        while i < len && chars[i].is_whitespace() {
+            i += 1;
+        }
+        while i < len && !chars[i].is_whitespace() {
+            i += 1;
+        }
+        self.cursor = i;
+    }
+
+    /// Skip any whitespace before the cursor, then skip back to the start
+    /// of the preceding word.
+    pub fn move_word_left(&mut self) {
+        self.cursor = word_left_boundary(&self.buffer, self.cursor);
+    }
+
+    /// Delete the word behind the cursor (`Ctrl+W`).
+    pub fn delete_word_left(&mut self) {
+        let boundary = word_left_boundary(&self.buffer, self.cursor);
+        let start = byte_index(&self.buffer, boundary);
+        let end = byte_index(&self.buffer, self.cursor);
+        self.buffer.replace_range(start..end, "");
+        self.cursor = boundary;
+        self.history_pos = None;
+    }
+
+    /// Delete from the cursor to the end of the buffer (`Ctrl+K`).
+    pub fn kill_to_end(&mut self) {
+        let start = byte_index(&self.buffer, self.cursor);
+        self.buffer.truncate(start);
+        self.history_pos = None;
+    }
+
+    /// Delete from the start of the buffer to the cursor (`Ctrl+U`).
+    pub fn kill_to_start(&mut self) {
+        let end = byte_index(&self.buffer, self.cursor);
+        self.buffer.replace_range(0..end, "");
+        self.cursor = 0;
+        self.history_pos = None;
+    }
+
+    /// True when there is no newline before the cursor, i.e. it sits on the
+    /// input box's first visual row.
+    pub fn cursor_on_first_line(&self) -> bool {
+        let idx = byte_index(&self.buffer, self.cursor);
+        !self.buffer[..idx].contains('\n')
+    }
+
+    /// True when there is no newline after the cursor, i.e. it sits on the
+    /// input box's last visual row.
+    pub fn cursor_on_last_line(&self) -> bool {
+        let idx = byte_index(&self.buffer, self.cursor);
+        !self.buffer[idx..].contains('\n')
+    }
+
+    /// Recall the previous history entry (`Up`), stashing the live buffer
+    /// as a draft the first time recall starts so it can be restored.
+    pub fn recall_prev(&mut self, history: &CommandHistory) {
+        let entries = history.entries();
+        if entries.is_empty() {
+            return;
+        }
+        let next_pos = match self.history_pos {
+            None => {
+                self.draft = self.buffer.clone();
+                entries.len() - 1
+            }
+            Some(0) => 0,
+            Some(p) => p - 1,
+        };
+        self.history_pos = Some(next_pos);
+        self.buffer = entries[next_pos].clone();
+        self.cursor = self.buffer.chars().count();
+    }
+
+    /// Recall the next (more recent) history entry (`Down`), restoring the
+    /// stashed draft once recall runs past the most recent entry.
+    pub fn recall_next(&mut self, history: &CommandHistory) {
+        let entries = history.entries();
+        match self.history_pos {
+            None => {}
+            Some(p) if p + 1 < entries.len() => {
+                self.history_pos = Some(p + 1);
+                self.buffer = entries[p + 1].clone();
+                self.cursor = self.buffer.chars().count();
+            }
+            Some(_) => {
+                self.history_pos = None;
+                self.buffer = std::mem::take(&mut self.draft);
+                self.cursor = self.buffer.chars().count();
+            }
+        }
+    }
+}
+
+fn word_left_boundary(buffer: &str, cursor: usize) -> usize {
+    let chars: Vec<char> = buffer.chars().collect();
+    let mut i = cursor;
+    while i > 0 && chars[i - 1].is_whitespace() {
+        i -= 1;
+    }
+    while i > 0 && !chars[i - 1].is_whitespace() {
+        i -= 1;
+    }
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_backspace() {
+        let mut editor = InputEditor::new();
+        editor.insert_char('h');
+        editor.insert_char('i');
+        assert_eq!(editor.as_str(), "hi");
+        editor.backspace();
+        assert_eq!(editor.as_str(), "h");
+        assert_eq!(editor.cursor_chars(), 1);
+    }
+
+    #[test]
+    fn test_insert_str_multiline_moves_cursor_past_it() {
+        let mut editor = InputEditor::new();
+        editor.insert_str("line1\nline2");
+        assert_eq!(editor.as_str(), "line1\nline2");
+        assert_eq!(editor.cursor_chars(), 11);
+        assert!(!editor.cursor_on_first_line());
+    }
+
+    #[test]
+    fn test_kill_to_start_and_end() {
+        let mut editor = InputEditor::new();
+        editor.insert_str("hello world");
+        editor.move_home();
+        editor.move_word_right();
+        editor.kill_to_start();
+        assert_eq!(editor.as_str(), " world");
+        editor.kill_to_end();
+        assert_eq!(editor.as_str(), "");
+    }
+
+    #[test]
+    fn test_move_word_left_right() {
+        let mut editor = InputEditor::new();
+        editor.insert_str("foo bar");
+        editor.move_word_left();
+        assert_eq!(editor.cursor_chars(), 4);
+        editor.move_word_left();
+        assert_eq!(editor.cursor_chars(), 0);
+        editor.move_word_right();
+        assert_eq!(editor.cursor_chars(), 3);
+    }
+
+    #[test]
+    fn test_recall_prev_next_restores_draft() {
+        let mut history = CommandHistory::in_memory();
+        history.push("first");
+        history.push("second");
+        let mut editor = InputEditor::new();
+        editor.insert_str("draft");
+        editor.recall_prev(&history);
+        assert_eq!(editor.as_str(), "second");
+        editor.recall_prev(&history);
+        assert_eq!(editor.as_str(), "first");
+        editor.recall_next(&history);
+        assert_eq!(editor.as_str(), "second");
+        editor.recall_next(&history);
+        assert_eq!(editor.as_str(), "draft");
+    }
+}
@@ -0,0 +1,241 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+
+/// One named gateway endpoint the TUI can connect to.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Endpoint {
+    pub name: String,
+    pub url: String,
+}
+
+/// Raw config file shape: a flat list of named endpoints.
+#[derive(Deserialize, Default)]
+struct EndpointsFile {
+    #[serde(default)]
+    endpoints: Vec<Endpoint>,
+}
+
+/// Coarse reachability derived from recent SSE/HTTP outcomes, bucketed by
+/// consecutive failures since the last success.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Health {
+    Reachable,
+    Degraded,
+    Down,
+}
+
+impl Health {
+    pub fn label(self) -> &'static str {
+        match self {
+            Health::Reachable => "reachable",
+            Health::Degraded => "degraded",
+            Health::Down => "down",
+        }
+    }
+}
+
+const DOWN_AFTER: u32 = 3;
+const BACKOFF_BASE: Duration = Duration::from_millis(500);
+const BACKOFF_MAX: Duration = Duration::from_secs(30);
+
+/// Per-endpoint bookkeeping used to derive [`Health`] and this endpoint's
+/// next reconnect backoff.
+pub struct EndpointStatus {
+    consecutive_failures: u32,
+    last_ok: Option<Instant>,
+}
+
+impl EndpointStatus {
+    fn new() -> Self {
+        Self {
+            consecutive_failures: 0,
+            last_ok: None,
+        }
+    }
+
+    pub fn health(&self) -> Health {
+        match self.consecutive_failures {
+            0 => Health::Reachable,
+            n if n < DOWN_AFTER => Health::Degraded,
+            _ => Health::Down,
+        }
+    }
+
+    /// Elapsed time since this endpoint last answered successfully, for the
+    /// "last connected N ago" status line.
+    pub fn since_last_ok(&self) -> Option<Duration> {
+        self.last_ok.map(|at| at.elapsed())
+    }
+
+    /// Exponential backoff from the current failure count, capped and with
+    /// up to 25% jitter so multiple reconnecting sessions don't retry in
+    /// lockstep. No external `rand` dependency: the jitter comes from the
+    /// wall clock's sub-second component, same trick used nowhere else yet
+    /// but cheap enough not to warrant a new crate.
+    fn backoff(&self) -> Duration {
+        let exp = self.consecutive_failures.min(8);
+        let scaled = BACKOFF_BASE.saturating_mul(1u32 << exp).min(BACKOFF_MAX);
+        let jitter_span = (scaled.as_millis() as u64 / 4).max(1);
+        scaled + jitter(jitter_span)
+    }
+}
+
+fn jitter(max_ms: u64) -> Duration {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    Duration::from_millis(u64::from(nanos) % max_ms)
+}
+
+/// Tracks the set of named gateway endpoints (loaded from config plus the
+/// local discovery probe), which one is active, and each endpoint's health.
+pub struct EndpointManager {
+    endpoints: Vec<Endpoint>,
+    statuses: Vec<EndpointStatus>,
+    pub active: usize,
+}
+
+impl EndpointManager {
+    /// Load named endpoints from `$XDG_CONFIG_HOME/magent2/endpoints.toml`,
+    /// always appending `discovered` (the `docker compose`/localhost
+    /// discovery probe result) as a trailing entry unless a config entry
+    /// already points at the same URL, so there's always at least one usable
+    /// endpoint even with no config file.
+    pub fn load(discovered: String) -> Self {
+        let mut endpoints: Vec<Endpoint> = config_path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| toml::from_str::<EndpointsFile>(&contents).ok())
+            .map(|file| file.endpoints)
+            .unwrap_or_default();
+        if !endpoints.iter().any(|e| e.url == discovered) {
+            endpoints.push(Endpoint {
+                name: "auto".to_string(),
+                url: discovered,
+            });
+        }
+        let statuses = endpoints.iter().map(|_| EndpointStatus::new()).collect();
+        Self {
+            endpoints,
+            statuses,
+            active: 0,
+        }
+    }
+
+    pub fn entries(&self) -> &[Endpoint] {
+        &self.endpoints
+    }
+
+    pub fn active_endpoint(&self) -> &Endpoint {
+        &self.endpoints[self.active]
+    }
+
+    pub fn active_url(&self) -> String {
+        self.active_endpoint().url.clone()
+    }
+
+    pub fn active_status(&self) -> &EndpointStatus {
+        &self.statuses[self.active]
+    }
+
+    pub fn status(&self, idx: usize) -> Option<&EndpointStatus> {
+        self.statuses.get(idx)
+    }
+
+    pub fn set_active(&mut self, idx: usize) {
+        if idx < self.endpoints.len() {
+            self.active = idx;
+        }
+    }
+
+    /// Record a successful SSE/HTTP outcome against the active endpoint.
+    pub fn record_success(&mut self) {
+        let status = &mut self.statuses[self.active];
+        status.consecutive_failures = 0;
+        status.last_ok = Some(Instant::now());
+    }
+
+    /// Record a failed SSE/HTTP outcome against the active endpoint,
+    /// returning the attempt count and the backoff to wait before retrying.
+    pub fn record_failure(&mut self) -> (u32, Duration) {
+        let status = &mut self.statuses[self.active];
+        status.consecutive_failures = status.consecutive_failures.saturating_add(1);
+        (status.consecutive_failures, status.backoff())
+    }
+}
+
+fn config_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join("magent2").join("endpoints.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_buckets_by_consecutive_failures() {
+        let mut status = EndpointStatus::new();
+        assert_eq!(status.health(), Health::Reachable);
+        status.consecutive_failures = 1;
+        assert_eq!(status.health(), Health::Degraded);
+        status.consecutive_failures = DOWN_AFTER;
+        assert_eq!(status.health(), Health::Down);
+    }
+
+    #[test]
+    fn test_record_success_resets_failures() {
+        let mut manager = EndpointManager {
+            endpoints: vec![Endpoint {
+                name: "auto".to_string(),
+                url: "http://localhost:1".to_string(),
+            }],
+            statuses: vec![EndpointStatus::new()],
+            active: 0,
+        };
+        manager.record_failure();
+        manager.record_failure();
+        assert_eq!(manager.active_status().health(), Health::Degraded);
+        manager.record_success();
+        assert_eq!(manager.active_status().health(), Health::Reachable);
+        assert!(manager.active_status().since_last_ok().is_some());
+    }
+
+    #[test]
+    fn test_record_failure_increments_and_backs_off() {
+        let mut manager = EndpointManager {
+            endpoints: vec![Endpoint {
+                name: "auto".to_string(),
+                url: "http://localhost:1".to_string(),
+            }],
+            statuses: vec![EndpointStatus::new()],
+            active: 0,
+        };
+        let (attempt, backoff) = manager.record_failure();
+        assert_eq!(attempt, 1);
+        assert!(backoff >= BACKOFF_BASE);
+        let (attempt, backoff2) = manager.record_failure();
+        assert_eq!(attempt, 2);
+        assert!(backoff2 >= backoff);
+    }
+
+    #[test]
+    fn test_load_appends_discovered_when_not_already_present() {
+        // No config file in the test environment, so `load` falls back to
+        // just the discovered endpoint.
+        let manager = EndpointManager::load("http://localhost:9999".to_string());
+        assert_eq!(manager.entries().len(), 1);
+        assert_eq!(manager.active_url(), "http://localhost:9999");
+    }
+
+    #[test]
+    fn test_set_active_ignores_out_of_range_index() {
+        let mut manager = EndpointManager::load("http://localhost:9999".to_string());
+        manager.set_active(5);
+        assert_eq!(manager.active, 0);
+    }
+}
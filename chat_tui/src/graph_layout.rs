@@ -0,0 +1,437 @@
+use std::collections::{HashMap, HashSet};
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+const MAX_LABEL_CHARS: usize = 18;
+const BARYCENTER_PASSES: usize = 4;
+
+/// A graph node as seen by the layout engine: just enough to place and
+/// style it, decoupled from the HTTP payload shape it's built from.
+pub struct Node<'a> {
+    pub id: &'a str,
+    pub kind: &'a str,
+}
+
+/// A directed edge with its occurrence count.
+pub struct Edge<'a> {
+    pub from: &'a str,
+    pub to: &'a str,
+    pub count: i64,
+}
+
+/// Arrow-key direction for moving the selected node around the layout.
+#[derive(Clone, Copy)]
+pub enum NavDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// Assign each node a layer via longest-path-from-roots (a node with no
+/// incoming edges sits at layer 0; otherwise it's one past the deepest
+/// predecessor), then order nodes within each layer by a few passes of the
+/// barycenter heuristic to reduce edge crossings. Returns one `Vec<String>`
+/// of node ids per layer, top (roots) to bottom.
+pub fn layered_order(nodes: &[Node], edges: &[Edge]) -> Vec<Vec<String>> {
+    if nodes.is_empty() {
+        return Vec::new();
+    }
+    let mut preds: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut succs: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in edges {
+        succs.entry(edge.from).or_default().push(edge.to);
+        preds.entry(edge.to).or_default().push(edge.from);
+    }
+
+    let mut layers: HashMap<String, usize> = HashMap::new();
+    let mut visiting: HashSet<String> = HashSet::new();
+    for node in nodes {
+        assign_layer(node.id, &preds, &mut layers, &mut visiting);
+    }
+
+    let max_layer = layers.values().copied().max().unwrap_or(0);
+    let mut by_layer: Vec<Vec<String>> = vec![Vec::new(); max_layer + 1];
+    for node in nodes {
+        let layer = layers.get(node.id).copied().unwrap_or(0);
+        by_layer[layer].push(node.id.to_string());
+    }
+    // A graph with no root (every node on a cycle) leaves layer 0 empty;
+    // drop empty layers so the first row always holds nodes.
+    by_layer.retain(|layer| !layer.is_empty());
+
+    for _ in 0..BARYCENTER_PASSES {
+        for i in 1..by_layer.len() {
+            barycenter_sort(&mut by_layer, i, i - 1, &preds);
+        }
+        for i in (0..by_layer.len().saturating_sub(1)).rev() {
+            barycenter_sort(&mut by_layer, i, i + 1, &succs);
+        }
+    }
+    by_layer
+}
+
+fn assign_layer(
+    id: &str,
+    preds: &HashMap<&str, Vec<&str>>,
+    layers: &mut HashMap<String, usize>,
+    visiting: &mut HashSet<String>,
+) -> usize {
+    if let Some(&l) = layers.get(id) {
+        return l;
+    }
+    if !visiting.insert(id.to_string()) {
+        // Already on the current recursion stack: a cycle. Treat it as a
+        // root rather than recursing forever.
+        return 0;
+    }
+    let l = match preds.get(id) {
+        Some(ps) if !ps.is_empty() => {
+            1 + ps
+                .iter()
+                .map(|p| assign_layer(p, preds, layers, visiting))
+                .max()
+                .unwrap_or(0)
+        }
+        _ => 0,
+    };
+    visiting.remove(id);
+    layers.insert(id.to_string(), l);
+    l
+}
+
+/// Reorder `by_layer[idx]` by the average position of each node's
+/// neighbors (looked up in `neighbors_of`) within `by_layer[adjacent_idx]`,
+/// falling back to the node's current position when it has no neighbors
+/// there so unrelated nodes don't all collapse to the front.
+fn barycenter_sort(
+    by_layer: &mut [Vec<String>],
+    idx: usize,
+    adjacent_idx: usize,
+    neighbors_of: &HashMap<&str, Vec<&str>>,
+) {
+    let adjacent_pos: HashMap<&str, usize> = by_layer[adjacent_idx]
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (id.as_str(), i))
+        .collect();
+    let mut scored: Vec<(f64, usize, String)> = by_layer[idx]
+        .iter()
+        .enumerate()
+        .map(|(current_pos, id)| {
+            let score = neighbors_of
+                .get(id.as_str())
+                .map(|ns| {
+                    let positions: Vec<usize> = ns
+                        .iter()
+                        .filter_map(|n| adjacent_pos.get(n).copied())
+                        .collect();
+                    if positions.is_empty() {
+                        current_pos as f64
+                    } else {
+                        positions.iter().sum::<usize>() as f64 / positions.len() as f64
+                    }
+                })
+                .unwrap_or(current_pos as f64);
+            (score, current_pos, id.clone())
+        })
+        .collect();
+    scored.sort_by(|a, b| {
+        a.0.partial_cmp(&b.0)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.1.cmp(&b.1))
+    });
+    by_layer[idx] = scored.into_iter().map(|(_, _, id)| id).collect();
+}
+
+/// Render the layered layout as styled lines: one row of boxed node ids per
+/// layer, a connector row between adjacent layers annotated with edge
+/// counts, a trailing list of any edges the connector rows couldn't place
+/// (back edges broken by a cycle, or edges skipping a layer), and an
+/// omitted-edges footer.
+pub fn render(
+    nodes: &[Node],
+    edges: &[Edge],
+    omitted_edges: usize,
+    selected: Option<&str>,
+) -> Vec<Line<'static>> {
+    if nodes.is_empty() {
+        return vec![Line::from("No graph data yet.")];
+    }
+    let layers = layered_order(nodes, edges);
+    let kind_of: HashMap<&str, &str> = nodes.iter().map(|n| (n.id, n.kind)).collect();
+    let layer_of: HashMap<&str, usize> = layers
+        .iter()
+        .enumerate()
+        .flat_map(|(i, ids)| ids.iter().map(move |id| (id.as_str(), i)))
+        .collect();
+    let mut succs: HashMap<&str, Vec<(&str, i64)>> = HashMap::new();
+    for edge in edges {
+        succs.entry(edge.from).or_default().push((edge.to, edge.count));
+    }
+
+    let mut lines: Vec<Line<'static>> = Vec::new();
+    for (i, layer) in layers.iter().enumerate() {
+        let mut spans: Vec<Span<'static>> = Vec::new();
+        for (j, id) in layer.iter().enumerate() {
+            if j > 0 {
+                spans.push(Span::raw("  "));
+            }
+            let kind = kind_of.get(id.as_str()).copied().unwrap_or("?");
+            let mut style = node_style(kind);
+            if selected == Some(id.as_str()) {
+                style = style.add_modifier(Modifier::REVERSED | Modifier::BOLD);
+            }
+            spans.push(Span::styled(format!("[{}]", truncate_label(id)), style));
+        }
+        lines.push(Line::from(spans));
+
+        if i + 1 < layers.len() {
+            if let Some(connectors) = connector_line(layer, i, &succs, &layer_of) {
+                lines.push(connectors);
+            }
+        }
+    }
+
+    let stray_edges: Vec<&Edge> = edges
+        .iter()
+        .filter(|e| {
+            let from_layer = layer_of.get(e.from).copied();
+            let to_layer = layer_of.get(e.to).copied();
+            !matches!((from_layer, to_layer), (Some(a), Some(b)) if b == a + 1)
+        })
+        .collect();
+    if !stray_edges.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::styled(
+            "Other edges:",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        for edge in stray_edges {
+            lines.push(Line::from(format!(
+                "  {} -> {} (x{})",
+                truncate_label(edge.from),
+                truncate_label(edge.to),
+                edge.count
+            )));
+        }
+    }
+
+    if omitted_edges > 0 {
+        lines.push(Line::styled(
+            format!("(+{} more edges omitted)", omitted_edges),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    lines
+}
+
+/// Build the connector row between layer `idx` and `idx + 1`: one marker
+/// per source node that has at least one edge landing directly in the next
+/// layer, annotated with that edge's count.
+fn connector_line(
+    layer: &[String],
+    idx: usize,
+    succs: &HashMap<&str, Vec<(&str, i64)>>,
+    layer_of: &HashMap<&str, usize>,
+) -> Option<Line<'static>> {
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    for id in layer {
+        let direct: Vec<i64> = succs
+            .get(id.as_str())
+            .map(|outs| {
+                outs.iter()
+                    .filter(|(to, _)| layer_of.get(to).copied() == Some(idx + 1))
+                    .map(|(_, count)| *count)
+                    .collect()
+            })
+            .unwrap_or_default();
+        if direct.is_empty() {
+            continue;
+        }
+        if !spans.is_empty() {
+            spans.push(Span::raw("  "));
+        }
+        let counts: Vec<String> = direct.iter().map(|c| c.to_string()).collect();
+        spans.push(Span::styled(
+            format!("│ {} →x{}", truncate_label(id), counts.join(",")),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    if spans.is_empty() {
+        None
+    } else {
+        Some(Line::from(spans))
+    }
+}
+
+fn node_style(kind: &str) -> Style {
+    match kind {
+        "tool" => Style::default().fg(Color::Magenta),
+        "model" => Style::default().fg(Color::Yellow),
+        "user" => Style::default().fg(Color::Cyan),
+        _ => Style::default().fg(Color::White),
+    }
+}
+
+fn truncate_label(id: &str) -> String {
+    let count = id.chars().count();
+    if count <= MAX_LABEL_CHARS {
+        return id.to_string();
+    }
+    let head: String = id.chars().take(MAX_LABEL_CHARS.saturating_sub(1)).collect();
+    format!("{}…", head)
+}
+
+/// Move the selection one step in `dir` across the layered grid, clamping
+/// at the edges instead of wrapping. Returns the first node of the first
+/// layer if nothing was previously selected (or the selected id no longer
+/// exists in the layout).
+pub fn move_selection(
+    layers: &[Vec<String>],
+    selected: Option<&str>,
+    dir: NavDirection,
+) -> Option<String> {
+    if layers.is_empty() {
+        return None;
+    }
+    let Some((cur_layer, cur_pos)) = selected.and_then(|id| find_position(layers, id)) else {
+        return layers.first().and_then(|l| l.first()).cloned();
+    };
+    match dir {
+        NavDirection::Left => {
+            if cur_pos > 0 {
+                Some(layers[cur_layer][cur_pos - 1].clone())
+            } else {
+                Some(layers[cur_layer][cur_pos].clone())
+            }
+        }
+        NavDirection::Right => {
+            if cur_pos + 1 < layers[cur_layer].len() {
+                Some(layers[cur_layer][cur_pos + 1].clone())
+            } else {
+                Some(layers[cur_layer][cur_pos].clone())
+            }
+        }
+        NavDirection::Up if cur_layer > 0 => {
+            Some(closest(&layers[cur_layer - 1], cur_pos, layers[cur_layer].len()))
+        }
+        NavDirection::Down if cur_layer + 1 < layers.len() => {
+            Some(closest(&layers[cur_layer + 1], cur_pos, layers[cur_layer].len()))
+        }
+        NavDirection::Up | NavDirection::Down => Some(layers[cur_layer][cur_pos].clone()),
+    }
+}
+
+fn find_position(layers: &[Vec<String>], id: &str) -> Option<(usize, usize)> {
+    for (i, layer) in layers.iter().enumerate() {
+        if let Some(j) = layer.iter().position(|n| n == id) {
+            return Some((i, j));
+        }
+    }
+    None
+}
+
+fn closest(target_layer: &[String], cur_pos: usize, cur_layer_len: usize) -> String {
+    if target_layer.is_empty() {
+        return String::new();
+    }
+    let ratio = if cur_layer_len <= 1 {
+        0.0
+    } else {
+        cur_pos as f64 / (cur_layer_len - 1) as f64
+    };
+    let idx = (ratio * (target_layer.len() - 1) as f64).round() as usize;
+    target_layer[idx.min(target_layer.len() - 1)].clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layered_order_places_roots_before_dependents() {
+        let nodes = vec![
+            Node { id: "a", kind: "user" },
+            Node { id: "b", kind: "tool" },
+            Node { id: "c", kind: "model" },
+        ];
+        let edges = vec![
+            Edge { from: "a", to: "b", count: 1 },
+            Edge { from: "b", to: "c", count: 1 },
+        ];
+        let layers = layered_order(&nodes, &edges);
+        assert_eq!(
+            layers,
+            vec![vec!["a".to_string()], vec!["b".to_string()], vec!["c".to_string()]]
+        );
+    }
+
+    #[test]
+    fn test_layered_order_breaks_cycles_instead_of_recursing_forever() {
+        let nodes = vec![Node { id: "a", kind: "tool" }, Node { id: "b", kind: "tool" }];
+        let edges = vec![
+            Edge { from: "a", to: "b", count: 1 },
+            Edge { from: "b", to: "a", count: 1 },
+        ];
+        let layers = layered_order(&nodes, &edges);
+        let placed: Vec<&String> = layers.iter().flatten().collect();
+        assert_eq!(placed.len(), 2);
+    }
+
+    #[test]
+    fn test_render_empty_nodes_shows_placeholder() {
+        let lines = render(&[], &[], 0, None);
+        assert_eq!(lines.len(), 1);
+    }
+
+    #[test]
+    fn test_truncate_label_adds_ellipsis_past_max_chars() {
+        let long = "x".repeat(MAX_LABEL_CHARS + 5);
+        let truncated = truncate_label(&long);
+        assert_eq!(truncated.chars().count(), MAX_LABEL_CHARS);
+        assert!(truncated.ends_with('…'));
+        assert_eq!(truncate_label("short"), "short");
+    }
+
+    #[test]
+    fn test_move_selection_left_right_clamp_at_row_edges() {
+        let layers = vec![vec!["a".to_string(), "b".to_string()]];
+        assert_eq!(
+            move_selection(&layers, Some("a"), NavDirection::Left),
+            Some("a".to_string())
+        );
+        assert_eq!(
+            move_selection(&layers, Some("a"), NavDirection::Right),
+            Some("b".to_string())
+        );
+        assert_eq!(
+            move_selection(&layers, Some("b"), NavDirection::Right),
+            Some("b".to_string())
+        );
+    }
+
+    #[test]
+    fn test_move_selection_none_selected_picks_first_node() {
+        let layers = vec![vec!["a".to_string()], vec!["b".to_string()]];
+        assert_eq!(
+            move_selection(&layers, None, NavDirection::Down),
+            Some("a".to_string())
+        );
+    }
+
+    #[test]
+    fn test_move_selection_up_down_crosses_layers() {
+        let layers = vec![vec!["a".to_string()], vec!["b".to_string()]];
+        assert_eq!(
+            move_selection(&layers, Some("a"), NavDirection::Down),
+            Some("b".to_string())
+        );
+        assert_eq!(
+            move_selection(&layers, Some("b"), NavDirection::Up),
+            Some("a".to_string())
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use ratatui::style::{Color, Style};
+
+/// Hand-rolled, best-effort tokenizer used to colorize fenced code blocks in
+/// model output. Not a full lexer for any language — just enough to make
+/// keywords, strings, comments, and numbers visually distinct.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Keyword,
+    String,
+    Comment,
+    Number,
+    Plain,
+}
+
+impl Token {
+    fn style(self) -> Style {
+        match self {
+            Token::Keyword => Style::default().fg(Color::Magenta),
+            Token::String => Style::default().fg(Color::Green),
+            Token::Comment => Style::default().fg(Color::DarkGray),
+            Token::Number => Style::default().fg(Color::Cyan),
+            Token::Plain => Style::default().fg(Color::White),
+        }
+    }
+}
+
+fn keywords_for(lang: &str) -> &'static [&'static str] {
+    match lang.to_lowercase().as_str() {
+        "rust" | "rs" => &[
+            "fn", "let", "mut", "struct", "enum", "impl", "trait", "pub", "use", "mod", "match",
+            "if", "else", "for", "while", "loop", "return", "async", "await", "const", "static",
+            "self", "Self", "true", "false", "as", "in", "break", "continue",
+        ],
+        "python" | "py" => &[
+            "def", "class", "import", "from", "as", "if", "elif", "else", "for", "while",
+            "return", "yield", "try", "except", "finally", "with", "lambda", "True", "False",
+            "None", "and", "or", "not", "in", "is",
+        ],
+        "javascript" | "js" | "typescript" | "ts" | "jsx" | "tsx" => &[
+            "function", "const", "let", "var", "if", "else", "for", "while", "return", "class",
+            "import", "export", "from", "async", "await", "true", "false", "null", "undefined",
+            "new", "this", "try", "catch", "finally",
+        ],
+        "go" => &[
+            "func", "package", "import", "var", "const", "type", "struct", "interface", "if",
+            "else", "for", "range", "return", "go", "chan", "select", "defer", "true", "false",
+            "nil",
+        ],
+        "bash" | "sh" | "shell" => &[
+            "if", "then", "else", "elif", "fi", "for", "do", "done", "while", "case", "esac",
+            "function", "echo", "export", "return",
+        ],
+        "json" => &["true", "false", "null"],
+        _ => &[],
+    }
+}
+
+fn is_comment_start(lang: &str, rest: &str) -> bool {
+    match lang.to_lowercase().as_str() {
+        "python" | "py" | "bash" | "sh" | "shell" => rest.starts_with('#'),
+        "rust" | "rs" | "javascript" | "js" | "typescript" | "ts" | "jsx" | "tsx" | "go" => {
+            rest.starts_with("//")
+        }
+        _ => false,
+    }
+}
+
+/// Tokenize a single line of source for `lang` into styled runs.
+/// Unrecognized languages fall back to a single plain-styled run.
+pub fn highlight_line(lang: &str, line: &str) -> Vec<(String, Style)> {
+    let keywords = keywords_for(lang);
+    if keywords.is_empty() && !is_comment_start(lang, line) {
+        return vec![(line.to_string(), Style::default().fg(Color::Gray))];
+    }
+
+    let mut spans: Vec<(String, Style)> = Vec::new();
+    let chars: Vec<char> = line.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let rest: String = chars[i..].iter().collect();
+        if is_comment_start(lang, &rest) {
+            spans.push((rest, Token::Comment.style()));
+            break;
+        }
+        let c = chars[i];
+        if c == '"' || c == '\'' {
+            let quote = c;
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i] != quote {
+                i += 1;
+            }
+            if i < chars.len() {
+                i += 1; // consume closing quote
+            }
+            let text: String = chars[start..i].iter().collect();
+            spans.push((text, Token::String.style()));
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            spans.push((text, Token::Number.style()));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            let token = if keywords.contains(&word.as_str()) {
+                Token::Keyword
+            } else {
+                Token::Plain
+            };
+            spans.push((word, token.style()));
+        } else {
+            let start = i;
+            i += 1;
+            let text: String = chars[start..i].iter().collect();
+            spans.push((text, Token::Plain.style()));
+        }
+    }
+    spans
+}
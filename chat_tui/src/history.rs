@@ -0,0 +1,212 @@
+use std::path::PathBuf;
+
+/// Maximum entries retained unless overridden by `MAGENT2_HISTORY_MAX`.
+const DEFAULT_MAX_ENTRIES: usize = 1000;
+
+/// Persisted, deduplicated command history shared by every session's input
+/// box. Kept as a small struct independent of the terminal so it can be
+/// unit-tested without a `ratatui`/`crossterm` runtime.
+pub struct CommandHistory {
+    path: Option<PathBuf>,
+    entries: Vec<String>,
+    max_entries: usize,
+}
+
+impl CommandHistory {
+    /// An in-memory history with no backing file, for tests and for
+    /// `InputEditor`'s recall unit tests — `push` becomes a no-op on disk.
+    #[cfg(test)]
+    pub(crate) fn in_memory() -> Self {
+        Self {
+            path: None,
+            entries: Vec::new(),
+            max_entries: DEFAULT_MAX_ENTRIES,
+        }
+    }
+
+    /// Load history from `$XDG_DATA_HOME/magent2/history.txt` (or
+    /// `~/.local/share` when unset), capped at `MAGENT2_HISTORY_MAX` entries
+    /// (default 1000). Missing or unreadable files just start empty.
+    pub fn load() -> Self {
+        let max_entries = std::env::var("MAGENT2_HISTORY_MAX")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_MAX_ENTRIES);
+        let path = data_path();
+        let entries = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .map(|contents| contents.lines().map(decode_entry).collect())
+            .unwrap_or_default();
+        let mut history = Self {
+            path,
+            entries,
+            max_entries,
+        };
+        history.truncate();
+        history
+    }
+
+    /// Entries oldest-first, as shown to `InputEditor`'s Up/Down recall.
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Append `text` unless it's empty or identical to the most recent
+    /// entry, cap at `max_entries`, and persist to disk (best-effort).
+    ///
+    /// `text` may itself contain newlines (e.g. a bracketed paste); those
+    /// are escaped on persist (see [`encode_entry`]) so the on-disk
+    /// newline-delimited format doesn't silently fragment it into multiple
+    /// entries.
+    pub fn push(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        if self.entries.last().map(String::as_str) == Some(text) {
+            return;
+        }
+        self.entries.push(text.to_string());
+        self.truncate();
+        self.persist();
+    }
+
+    /// Search newest-first for the `skip`-th most recent entry containing
+    /// `query` as a (case-insensitive) subsequence, e.g. `"cgt"` matches
+    /// `"cargo test"`. An empty query never matches.
+    pub fn search_subsequence(&self, query: &str, skip: usize) -> Option<&str> {
+        if query.is_empty() {
+            return None;
+        }
+        let query = query.to_lowercase();
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| is_subsequence(&query, &entry.to_lowercase()))
+            .nth(skip)
+            .map(String::as_str)
+    }
+
+    fn truncate(&mut self) {
+        if self.entries.len() > self.max_entries {
+            let excess = self.entries.len() - self.max_entries;
+            self.entries.drain(0..excess);
+        }
+    }
+
+    fn persist(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            if std::fs::create_dir_all(parent).is_err() {
+                return;
+            }
+        }
+        let mut contents = String::new();
+        for entry in &self.entries {
+            contents.push_str(&encode_entry(entry));
+            contents.push('\n');
+        }
+        let _ = std::fs::write(path, contents);
+    }
+}
+
+/// Escape a single entry for the newline-delimited on-disk format: literal
+/// backslashes and newlines become `\\` and `\n` so a multi-line entry
+/// (e.g. a pasted block) round-trips as one line instead of fragmenting
+/// into several on the next [`CommandHistory::load`].
+fn encode_entry(entry: &str) -> String {
+    let mut out = String::with_capacity(entry.len());
+    for c in entry.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Reverse of [`encode_entry`].
+fn decode_entry(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('\\') => out.push('\\'),
+                Some(other) => {
+                    out.push('\\');
+                    out.push(other);
+                }
+                None => out.push('\\'),
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn is_subsequence(query: &str, candidate: &str) -> bool {
+    let mut chars = candidate.chars();
+    query
+        .chars()
+        .all(|qc| chars.any(|cc| cc == qc))
+}
+
+fn data_path() -> Option<PathBuf> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))?;
+    Some(data_home.join("magent2").join("history.txt"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_dedups_consecutive_and_skips_empty() {
+        let mut history = CommandHistory::in_memory();
+        history.push("a");
+        history.push("a");
+        history.push("");
+        history.push("b");
+        assert_eq!(history.entries(), &["a", "b"]);
+    }
+
+    #[test]
+    fn test_search_subsequence_newest_first() {
+        let mut history = CommandHistory::in_memory();
+        history.push("cargo build");
+        history.push("cargo test");
+        history.push("git status");
+        // "t" is a subsequence of "git status" and "cargo test" but not
+        // "cargo build"; newest match comes back first, older ones by skip.
+        assert_eq!(history.search_subsequence("t", 0), Some("git status"));
+        assert_eq!(history.search_subsequence("t", 1), Some("cargo test"));
+        assert_eq!(history.search_subsequence("t", 2), None);
+        assert_eq!(history.search_subsequence("", 0), None);
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip_multiline() {
+        let original = "line1\nline2\\with backslash";
+        let encoded = encode_entry(original);
+        assert!(!encoded.contains('\n'));
+        assert_eq!(decode_entry(&encoded), original);
+    }
+
+    #[test]
+    fn test_truncate_drops_oldest() {
+        let mut history = CommandHistory::in_memory();
+        history.max_entries = 2;
+        history.push("a");
+        history.push("b");
+        history.push("c");
+        assert_eq!(history.entries(), &["b", "c"]);
+    }
+}
@@ -0,0 +1,372 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+/// Named actions that a key chord can be bound to.
+///
+/// Only the chorded / special-key bindings are modelled here; plain
+/// character input and contextual scroll/selection navigation stay inline
+/// in `handle_key_event` since their behavior depends on focus state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    ClearChat,
+    ClearInput,
+    ToggleConversations,
+    RefreshConversations,
+    ToggleAgents,
+    ToggleGraph,
+    ToggleEvents,
+    ToggleEndpoints,
+    ToggleHelp,
+    TogglePalette,
+    Send,
+    NextSession,
+    NewSession,
+    Quit,
+    GrowPane,
+    ShrinkPane,
+    GrowSide,
+    ShrinkSide,
+    ToggleDockDirection,
+}
+
+impl Action {
+    const ALL: [Action; 19] = [
+        Action::ClearChat,
+        Action::ClearInput,
+        Action::ToggleConversations,
+        Action::RefreshConversations,
+        Action::ToggleAgents,
+        Action::ToggleGraph,
+        Action::ToggleEvents,
+        Action::ToggleEndpoints,
+        Action::ToggleHelp,
+        Action::TogglePalette,
+        Action::Send,
+        Action::NextSession,
+        Action::NewSession,
+        Action::Quit,
+        Action::GrowPane,
+        Action::ShrinkPane,
+        Action::GrowSide,
+        Action::ShrinkSide,
+        Action::ToggleDockDirection,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Action::ClearChat => "clear_chat",
+            Action::ClearInput => "clear_input",
+            Action::ToggleConversations => "toggle_conversations",
+            Action::RefreshConversations => "refresh_conversations",
+            Action::ToggleAgents => "toggle_agents",
+            Action::ToggleGraph => "toggle_graph",
+            Action::ToggleEvents => "toggle_events",
+            Action::ToggleEndpoints => "toggle_endpoints",
+            Action::ToggleHelp => "toggle_help",
+            Action::TogglePalette => "toggle_palette",
+            Action::Send => "send",
+            Action::NextSession => "next_session",
+            Action::NewSession => "new_session",
+            Action::Quit => "quit",
+            Action::GrowPane => "grow_pane",
+            Action::ShrinkPane => "shrink_pane",
+            Action::GrowSide => "grow_side",
+            Action::ShrinkSide => "shrink_side",
+            Action::ToggleDockDirection => "toggle_dock_direction",
+        }
+    }
+
+    fn default_chord(self) -> KeyChord {
+        match self {
+            Action::ClearChat => KeyChord::ctrl(KeyCode::Char('l')),
+            // Not Ctrl+U (or Ctrl+K): handle_input_editing_key binds those to
+            // kill-to-start/kill-to-end directly, ahead of the keymap, so
+            // neither ever reaches resolve() while the input box has focus.
+            Action::ClearInput => KeyChord::ctrl(KeyCode::Char('x')),
+            Action::ToggleConversations => KeyChord::ctrl(KeyCode::Char('c')),
+            Action::RefreshConversations => KeyChord::ctrl(KeyCode::Char('r')),
+            Action::ToggleAgents => KeyChord::ctrl(KeyCode::Char('a')),
+            Action::ToggleGraph => KeyChord::ctrl(KeyCode::Char('g')),
+            Action::ToggleEvents => KeyChord::ctrl(KeyCode::Char('e')),
+            Action::ToggleEndpoints => KeyChord::ctrl(KeyCode::Char('o')),
+            // Not bound to the plain '?' chord: that would make it
+            // impossible to type a literal '?' into the input box, since
+            // KeyChord::from_event ignores Shift and the keymap is resolved
+            // before plain character insertion.
+            Action::ToggleHelp => KeyChord::plain(KeyCode::F(1)),
+            Action::TogglePalette => KeyChord::ctrl(KeyCode::Char('p')),
+            Action::Send => KeyChord::plain(KeyCode::Enter),
+            Action::NextSession => KeyChord::plain(KeyCode::Tab),
+            Action::NewSession => KeyChord::plain(KeyCode::F(2)),
+            Action::Quit => KeyChord::plain(KeyCode::Esc),
+            Action::GrowPane => KeyChord::ctrl(KeyCode::Up),
+            Action::ShrinkPane => KeyChord::ctrl(KeyCode::Down),
+            Action::GrowSide => KeyChord::ctrl(KeyCode::Right),
+            Action::ShrinkSide => KeyChord::ctrl(KeyCode::Left),
+            Action::ToggleDockDirection => KeyChord::ctrl(KeyCode::Char('d')),
+        }
+    }
+}
+
+/// A key chord: a `KeyCode` plus the modifiers that must be held.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    fn plain(code: KeyCode) -> Self {
+        Self {
+            code,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    fn ctrl(code: KeyCode) -> Self {
+        Self {
+            code,
+            modifiers: KeyModifiers::CONTROL,
+        }
+    }
+
+    fn from_event(key: KeyEvent) -> Self {
+        // Shift is ignored for character keys: '?' already implies shift on
+        // most layouts, and we don't want Shift+letter to miss Ctrl chords.
+        // For non-character keys (F-keys, arrows, ...) there's no such
+        // ambiguity, so match it — otherwise a configured `Shift+F1` binding
+        // would show up in the help overlay but could never fire.
+        let mask = match key.code {
+            KeyCode::Char(_) => KeyModifiers::CONTROL | KeyModifiers::ALT,
+            _ => KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT,
+        };
+        Self {
+            code: key.code,
+            modifiers: key.modifiers & mask,
+        }
+    }
+
+    /// Parse a chord string like `"Ctrl+L"`, `"Alt+Enter"`, or `"?"`.
+    fn parse(spec: &str) -> Option<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut parts = spec.split('+').map(str::trim).peekable();
+        let mut last = String::new();
+        while let Some(part) = parts.next() {
+            if parts.peek().is_none() {
+                last = part.to_string();
+                break;
+            }
+            match part.to_lowercase().as_str() {
+                "ctrl" | "control" => modifiers |= KeyModifiers::CONTROL,
+                "alt" => modifiers |= KeyModifiers::ALT,
+                "shift" => modifiers |= KeyModifiers::SHIFT,
+                _ => return None,
+            }
+        }
+        let code = match last.to_lowercase().as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "f1" => KeyCode::F(1),
+            "f2" => KeyCode::F(2),
+            _ => {
+                let mut chars = last.chars();
+                let c = chars.next()?;
+                if chars.next().is_some() {
+                    return None;
+                }
+                KeyCode::Char(c)
+            }
+        };
+        Some(Self { code, modifiers })
+    }
+
+    fn display(self) -> String {
+        let mut parts = Vec::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            parts.push("Ctrl".to_string());
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            parts.push("Alt".to_string());
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            parts.push("Shift".to_string());
+        }
+        let key = match self.code {
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::BackTab => "BackTab".to_string(),
+            KeyCode::Backspace => "Backspace".to_string(),
+            KeyCode::Up => "Up".to_string(),
+            KeyCode::Down => "Down".to_string(),
+            KeyCode::Left => "Left".to_string(),
+            KeyCode::Right => "Right".to_string(),
+            KeyCode::F(n) => format!("F{}", n),
+            other => format!("{:?}", other),
+        };
+        parts.push(key);
+        parts.join("+")
+    }
+}
+
+/// Raw keymap config as loaded from TOML/JSON: action name -> chord string.
+#[derive(Deserialize, Default)]
+struct KeymapFile {
+    #[serde(flatten)]
+    bindings: HashMap<String, String>,
+}
+
+/// Resolves key events to [`Action`]s through a configurable table, falling
+/// back to the built-in defaults for anything not overridden.
+pub struct Keymap {
+    chord_to_action: HashMap<KeyChord, Action>,
+    action_to_chord: HashMap<Action, KeyChord>,
+}
+
+impl Keymap {
+    fn with_defaults() -> Self {
+        let mut chord_to_action = HashMap::new();
+        let mut action_to_chord = HashMap::new();
+        for action in Action::ALL {
+            let chord = action.default_chord();
+            chord_to_action.insert(chord, action);
+            action_to_chord.insert(action, chord);
+        }
+        Self {
+            chord_to_action,
+            action_to_chord,
+        }
+    }
+
+    /// Load the keymap from `$XDG_CONFIG_HOME/magent2/keymap.toml`, falling
+    /// back to defaults when the file is absent or fails to parse.
+    pub fn load() -> Self {
+        let mut keymap = Self::with_defaults();
+        if let Some(path) = config_path() {
+            if let Ok(contents) = std::fs::read_to_string(&path) {
+                let parsed = if path.extension().and_then(|e| e.to_str()) == Some("json") {
+                    serde_json::from_str::<KeymapFile>(&contents).ok()
+                } else {
+                    toml::from_str::<KeymapFile>(&contents).ok()
+                };
+                if let Some(file) = parsed {
+                    keymap.apply_overrides(&file.bindings);
+                }
+            }
+        }
+        keymap
+    }
+
+    fn apply_overrides(&mut self, bindings: &HashMap<String, String>) {
+        for action in Action::ALL {
+            if let Some(spec) = bindings.get(action.name()) {
+                if let Some(chord) = KeyChord::parse(spec) {
+                    let old = self.action_to_chord.insert(action, chord);
+                    if let Some(old) = old {
+                        self.chord_to_action.remove(&old);
+                    }
+                    self.chord_to_action.insert(chord, action);
+                }
+            }
+        }
+    }
+
+    pub fn resolve(&self, key: KeyEvent) -> Option<Action> {
+        self.chord_to_action.get(&KeyChord::from_event(key)).copied()
+    }
+
+    /// Entries for the help overlay: action name and its currently-bound chord.
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        let mut entries: Vec<(&'static str, String)> = Action::ALL
+            .iter()
+            .map(|action| {
+                let chord = self.action_to_chord[action];
+                (action.name(), chord.display())
+            })
+            .collect();
+        entries.sort_by_key(|(name, _)| *name);
+        entries
+    }
+}
+
+fn config_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config"))
+        })?;
+    Some(config_home.join("magent2").join("keymap.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ctrl_and_plain_chords() {
+        assert_eq!(
+            KeyChord::parse("Ctrl+l"),
+            Some(KeyChord::ctrl(KeyCode::Char('l')))
+        );
+        assert_eq!(KeyChord::parse("F1"), Some(KeyChord::plain(KeyCode::F(1))));
+        assert_eq!(KeyChord::parse("?"), Some(KeyChord::plain(KeyCode::Char('?'))));
+        assert_eq!(KeyChord::parse("not-a-key"), None);
+    }
+
+    #[test]
+    fn test_parse_display_round_trip() {
+        let chord = KeyChord::parse("Ctrl+Alt+Up").unwrap();
+        assert_eq!(chord.display(), "Ctrl+Alt+Up");
+    }
+
+    #[test]
+    fn test_resolve_uses_defaults_and_ignores_shift_on_chars() {
+        let keymap = Keymap::with_defaults();
+        let plain_help = KeyEvent::new(KeyCode::F(1), KeyModifiers::NONE);
+        assert_eq!(keymap.resolve(plain_help), Some(Action::ToggleHelp));
+
+        let shifted_char = KeyEvent::new(KeyCode::Char('l'), KeyModifiers::CONTROL | KeyModifiers::SHIFT);
+        assert_eq!(keymap.resolve(shifted_char), Some(Action::ClearChat));
+    }
+
+    #[test]
+    fn test_resolve_matches_shift_on_non_character_keys() {
+        let mut keymap = Keymap::with_defaults();
+        keymap.apply_overrides(&HashMap::from([(
+            "toggle_help".to_string(),
+            "Shift+F1".to_string(),
+        )]));
+        let plain = KeyEvent::new(KeyCode::F(1), KeyModifiers::NONE);
+        assert_eq!(keymap.resolve(plain), None);
+        let shifted = KeyEvent::new(KeyCode::F(1), KeyModifiers::SHIFT);
+        assert_eq!(keymap.resolve(shifted), Some(Action::ToggleHelp));
+    }
+
+    #[test]
+    fn test_apply_overrides_replaces_default_binding() {
+        let mut keymap = Keymap::with_defaults();
+        keymap.apply_overrides(&HashMap::from([(
+            "clear_chat".to_string(),
+            "Ctrl+z".to_string(),
+        )]));
+        assert_eq!(
+            keymap.resolve(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::CONTROL)),
+            None
+        );
+        assert_eq!(
+            keymap.resolve(KeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL)),
+            Some(Action::ClearChat)
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// Side panes that can be tiled alongside the always-visible chat/input
+/// column. `ALL` also fixes the default stacking order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Pane {
+    Agents,
+    Graph,
+    Events,
+}
+
+impl Pane {
+    pub const ALL: [Pane; 3] = [Pane::Agents, Pane::Graph, Pane::Events];
+}
+
+/// One pane's persisted state: whether it's currently shown, and its share
+/// of the dock column relative to the other visible panes (an arbitrary
+/// weight, not a percentage — siblings are split proportionally).
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct PaneState {
+    pub pane: Pane,
+    pub visible: bool,
+    pub ratio: u16,
+}
+
+const DEFAULT_RATIO: u16 = 10;
+const MIN_RATIO: u16 = 2;
+const MAX_RATIO: u16 = 30;
+const RATIO_STEP: u16 = 2;
+
+const DEFAULT_SIDE_PERCENT: u16 = 35;
+const MIN_SIDE_PERCENT: u16 = 15;
+const MAX_SIDE_PERCENT: u16 = 70;
+const SIDE_STEP: u16 = 5;
+
+/// A dock-style layout for the side panes: how wide the dock column is
+/// relative to chat, whether its panes stack vertically or horizontally,
+/// each pane's visibility and relative share, and which pane last had
+/// focus. Persisted to `$XDG_CONFIG_HOME/magent2/layout.toml` so the
+/// workspace survives restarts; missing or unreadable files fall back to
+/// `Pane::ALL` all hidden with even ratios.
+#[derive(Serialize, Deserialize)]
+pub struct DockLayout {
+    pub side_percent: u16,
+    #[serde(default)]
+    pub stack_horizontal: bool,
+    pub panes: Vec<PaneState>,
+    #[serde(default)]
+    pub focus: Option<Pane>,
+}
+
+impl DockLayout {
+    fn default_panes() -> Vec<PaneState> {
+        Pane::ALL
+            .iter()
+            .map(|&pane| PaneState {
+                pane,
+                visible: false,
+                ratio: DEFAULT_RATIO,
+            })
+            .collect()
+    }
+
+    pub fn load() -> Self {
+        config_path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| toml::from_str::<DockLayout>(&contents).ok())
+            .unwrap_or_else(|| Self {
+                side_percent: DEFAULT_SIDE_PERCENT,
+                stack_horizontal: false,
+                panes: Self::default_panes(),
+                focus: None,
+            })
+    }
+
+    /// Best-effort write to disk; failures (missing home dir, read-only
+    /// filesystem) are silently ignored, same as `CommandHistory::persist`.
+    pub fn save(&self) {
+        let Some(path) = config_path() else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            if std::fs::create_dir_all(parent).is_err() {
+                return;
+            }
+        }
+        if let Ok(contents) = toml::to_string(self) {
+            let _ = std::fs::write(path, contents);
+        }
+    }
+
+    fn state_mut(&mut self, pane: Pane) -> &mut PaneState {
+        let idx = self
+            .panes
+            .iter()
+            .position(|p| p.pane == pane)
+            .unwrap_or_else(|| {
+                self.panes.push(PaneState {
+                    pane,
+                    visible: false,
+                    ratio: DEFAULT_RATIO,
+                });
+                self.panes.len() - 1
+            });
+        &mut self.panes[idx]
+    }
+
+    pub fn is_visible(&self, pane: Pane) -> bool {
+        self.panes.iter().any(|p| p.pane == pane && p.visible)
+    }
+
+    pub fn set_visible(&mut self, pane: Pane, visible: bool) {
+        self.state_mut(pane).visible = visible;
+    }
+
+    /// Visible panes in stacking order, with their relative ratio weight.
+    pub fn visible_panes(&self) -> Vec<(Pane, u16)> {
+        self.panes
+            .iter()
+            .filter(|p| p.visible)
+            .map(|p| (p.pane, p.ratio))
+            .collect()
+    }
+
+    pub fn grow(&mut self, pane: Pane) {
+        let state = self.state_mut(pane);
+        state.ratio = (state.ratio + RATIO_STEP).min(MAX_RATIO);
+    }
+
+    pub fn shrink(&mut self, pane: Pane) {
+        let state = self.state_mut(pane);
+        state.ratio = state.ratio.saturating_sub(RATIO_STEP).max(MIN_RATIO);
+    }
+
+    pub fn grow_side(&mut self) {
+        self.side_percent = (self.side_percent + SIDE_STEP).min(MAX_SIDE_PERCENT);
+    }
+
+    pub fn shrink_side(&mut self) {
+        self.side_percent = self.side_percent.saturating_sub(SIDE_STEP).max(MIN_SIDE_PERCENT);
+    }
+
+    pub fn toggle_direction(&mut self) {
+        self.stack_horizontal = !self.stack_horizontal;
+    }
+}
+
+fn config_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join("magent2").join("layout.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn default_layout() -> DockLayout {
+        DockLayout {
+            side_percent: DEFAULT_SIDE_PERCENT,
+            stack_horizontal: false,
+            panes: DockLayout::default_panes(),
+            focus: None,
+        }
+    }
+
+    #[test]
+    fn test_set_visible_and_is_visible() {
+        let mut layout = default_layout();
+        assert!(!layout.is_visible(Pane::Agents));
+        layout.set_visible(Pane::Agents, true);
+        assert!(layout.is_visible(Pane::Agents));
+        assert_eq!(layout.visible_panes(), vec![(Pane::Agents, DEFAULT_RATIO)]);
+    }
+
+    #[test]
+    fn test_grow_shrink_clamped_to_bounds() {
+        let mut layout = default_layout();
+        for _ in 0..(MAX_RATIO / RATIO_STEP + 5) {
+            layout.grow(Pane::Graph);
+        }
+        assert_eq!(layout.state_mut(Pane::Graph).ratio, MAX_RATIO);
+        for _ in 0..(MAX_RATIO / RATIO_STEP + 5) {
+            layout.shrink(Pane::Graph);
+        }
+        assert_eq!(layout.state_mut(Pane::Graph).ratio, MIN_RATIO);
+    }
+
+    #[test]
+    fn test_grow_shrink_side_clamped_to_bounds() {
+        let mut layout = default_layout();
+        for _ in 0..20 {
+            layout.grow_side();
+        }
+        assert_eq!(layout.side_percent, MAX_SIDE_PERCENT);
+        for _ in 0..20 {
+            layout.shrink_side();
+        }
+        assert_eq!(layout.side_percent, MIN_SIDE_PERCENT);
+    }
+
+    #[test]
+    fn test_toggle_direction() {
+        let mut layout = default_layout();
+        assert!(!layout.stack_horizontal);
+        layout.toggle_direction();
+        assert!(layout.stack_horizontal);
+    }
+}
@@ -1,25 +1,51 @@
+use std::collections::VecDeque;
 use std::io::{self, Write};
 use std::process::Command;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use crossterm::event::{
-    self, DisableBracketedPaste, EnableBracketedPaste, Event as CEvent, KeyCode, KeyEvent,
-    KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+    Event as CEvent, KeyCode, KeyEvent, KeyModifiers, KeyboardEnhancementFlags, MouseButton,
+    MouseEvent, MouseEventKind, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
 };
 use crossterm::terminal::ScrollUp;
 use crossterm::{cursor, execute};
 use ratatui::prelude::*;
 use ratatui::text::Line;
-use ratatui::widgets::{Block, Borders, Paragraph, Tabs, Wrap};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Tabs, Wrap};
 use reqwest::{Client, StatusCode};
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use tokio::task::JoinHandle;
 // serde_json used via fully qualified path in parsing; no direct import needed
-use pulldown_cmark::{Event as MdEvent, Options as MdOptions, Parser as MdParser, Tag, TagEnd};
 use serde::Deserialize;
 use unicode_width::UnicodeWidthStr;
 
+mod editor;
+mod endpoints;
+mod graph_layout;
+mod highlight;
+mod history;
+mod keymap;
+mod layout;
+mod notify;
+mod palette;
+mod rich_text;
 mod sse;
+mod store;
+mod token_meter;
+mod webhook;
+
+use editor::InputEditor;
+use graph_layout::NavDirection;
+use endpoints::{EndpointManager, Health};
+use history::CommandHistory;
+use keymap::{Action, Keymap};
+use layout::{DockLayout, Pane};
+use notify::Notifier;
+use palette::{PaletteEntry, PaletteTarget};
+use store::{SessionStore, StoredMessage, StoredSession};
+use token_meter::TokenMeter;
+use webhook::{Webhook, WebhookEvent};
 
 /// Create a shared HTTP client with proper configuration
 /// - Connection timeout for reliability
@@ -37,6 +63,7 @@ const SPINNER_FRAMES: [&str; 4] = [".", "..", "...", ".."];
 const AGENTS_REFRESH_MS: u64 = 3_000;
 const GRAPH_REFRESH_MS: u64 = 5_000;
 const GRAPH_EDGE_LIMIT: usize = 120;
+const EVENTS_LOG_CAPACITY: usize = 500;
 
 // UI event bus carrying structured events to the render loop.
 enum UiEvent {
@@ -83,6 +110,41 @@ enum UiEvent {
     },
 }
 
+/// Decode a `UiEvent` into `(kind, idx, gen, payload)` for the event
+/// inspector panel, without consuming it.
+fn describe_ui_event(evt: &UiEvent) -> (&'static str, usize, u64, String) {
+    match evt {
+        UiEvent::User { idx, gen, text } => ("User", *idx, *gen, format!("text: {:?}", text)),
+        UiEvent::ModelToken { idx, gen, text } => {
+            ("ModelToken", *idx, *gen, format!("text: {:?}", text))
+        }
+        UiEvent::ModelOutput { idx, gen, text } => {
+            ("ModelOutput", *idx, *gen, format!("text: {:?}", text))
+        }
+        UiEvent::Tool { idx, gen, text } => ("Tool", *idx, *gen, format!("text: {:?}", text)),
+        UiEvent::ToolStep {
+            idx,
+            gen,
+            name,
+            status,
+            summary,
+        } => (
+            "ToolStep",
+            *idx,
+            *gen,
+            format!(
+                "name: {:?}\nstatus: {:?}\nsummary: {:?}",
+                name, status, summary
+            ),
+        ),
+        UiEvent::StreamError { idx, gen, message } => {
+            ("StreamError", *idx, *gen, format!("message: {:?}", message))
+        }
+        UiEvent::StreamClosed { idx, gen } => ("StreamClosed", *idx, *gen, String::new()),
+        UiEvent::SetLastId { idx, gen, id } => ("SetLastId", *idx, *gen, format!("id: {:?}", id)),
+    }
+}
+
 #[derive(Clone, Copy)]
 enum Speaker {
     User,
@@ -90,6 +152,24 @@ enum Speaker {
     Tool,
 }
 
+impl Speaker {
+    fn as_str(self) -> &'static str {
+        match self {
+            Speaker::User => "user",
+            Speaker::Model => "model",
+            Speaker::Tool => "tool",
+        }
+    }
+
+    fn from_str(s: &str) -> Self {
+        match s {
+            "user" => Speaker::User,
+            "tool" => Speaker::Tool,
+            _ => Speaker::Model,
+        }
+    }
+}
+
 struct Message {
     speaker: Speaker,
     content: String,
@@ -102,6 +182,9 @@ enum BusyReason {
         status: String,
         summary: Option<String>,
     },
+    Reconnecting {
+        attempt: u32,
+    },
     Error {
         message: String,
     },
@@ -116,7 +199,7 @@ struct AgentRow {
     name: String,
     active_runs: u64,
     last_seen: Option<SystemTime>,
-    recent_conversations: usize,
+    recent_conversations: Vec<String>,
 }
 
 struct GraphNode {
@@ -136,16 +219,30 @@ struct GraphData {
     omitted_edges: usize,
 }
 
+/// One captured `UiEvent`, decoded into a display-friendly shape for the
+/// event inspector panel.
+struct LoggedEvent {
+    at: Instant,
+    idx: usize,
+    gen: u64,
+    conversation_id: Option<String>,
+    kind: &'static str,
+    payload: String,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum FocusTarget {
     Input,
     Conversations,
+    Agents,
+    Graph,
+    Events,
 }
 
 struct ChatSession {
     title: String,
     messages: Vec<Message>,
-    input: String,
+    input: InputEditor,
     gen: u64, // increments each time user sends; filters stale stream tasks
     last_sse_id: Option<String>,
     scroll: u16,
@@ -155,6 +252,8 @@ struct ChatSession {
     stream_task: Option<JoinHandle<()>>, // abort previous SSE task on new send
     conversation_id: Option<String>,     // None until first send, then set to new id
     busy: Option<BusyState>,
+    unread: bool,                  // a response finished here while the user was on another tab
+    notified_output_gen: Option<u64>, // gen already notified via ModelOutput, so StreamClosed doesn't double-notify
 }
 
 struct AppState {
@@ -172,13 +271,44 @@ struct AppState {
     agents: Vec<AgentRow>,
     agents_last_fetch: Option<Instant>,
     agents_error: Option<String>,
+    agents_selected: usize,
+    agents_detail: bool,
     show_graph: bool,
     graph: Option<GraphData>,
     graph_for_conversation: Option<String>,
     graph_last_fetch: Option<Instant>,
     graph_error: Option<String>,
+    graph_selected: Option<String>,
+    graph_pinned: bool,
     focus: FocusTarget,
+    tabs_area: Rect,
+    chat_area: Rect,
+    conversations_area: Option<Rect>,
+    input_area: Rect,
     http_client: Client,
+    keymap: Keymap,
+    show_help: bool,
+    notifier: Notifier,
+    store: Option<SessionStore>,
+    webhook: Webhook,
+    show_palette: bool,
+    palette_query: String,
+    palette_selected: usize,
+    palette_entries: Vec<PaletteEntry>,
+    token_meter: Option<TokenMeter>,
+    show_events: bool,
+    events_log: VecDeque<LoggedEvent>,
+    events_paused: bool,
+    events_filter: String,
+    events_selected: usize,
+    history: CommandHistory,
+    show_history_search: bool,
+    history_query: String,
+    history_skip: usize,
+    dock: DockLayout,
+    endpoints: EndpointManager,
+    show_endpoints: bool,
+    endpoints_selected: usize,
 }
 
 impl ChatSession {
@@ -193,6 +323,11 @@ impl ChatSession {
             {
                 existing.since
             }
+            (Some(existing), BusyReason::Reconnecting { .. })
+                if matches!(existing.reason, BusyReason::Reconnecting { .. }) =>
+            {
+                existing.since
+            }
             _ => Instant::now(),
         };
         self.busy = Some(BusyState { since, reason });
@@ -201,6 +336,56 @@ impl ChatSession {
     fn clear_busy(&mut self) {
         self.busy = None;
     }
+
+    fn new(title: String) -> Self {
+        Self {
+            title,
+            messages: Vec::new(),
+            input: InputEditor::new(),
+            gen: 0,
+            last_sse_id: None,
+            scroll: 0,
+            max_scroll: 0,
+            viewport_height: 0,
+            follow: true,
+            stream_task: None,
+            conversation_id: None,
+            busy: None,
+            unread: false,
+            notified_output_gen: None,
+        }
+    }
+
+    fn from_stored(stored: StoredSession) -> Self {
+        let mut session = Self::new(stored.title);
+        session.conversation_id = stored.conversation_id;
+        session.last_sse_id = stored.last_sse_id;
+        session.messages = stored
+            .messages
+            .into_iter()
+            .map(|m| Message {
+                speaker: Speaker::from_str(&m.speaker),
+                content: m.content,
+            })
+            .collect();
+        session
+    }
+
+    fn to_stored(&self) -> StoredSession {
+        StoredSession {
+            title: self.title.clone(),
+            conversation_id: self.conversation_id.clone(),
+            last_sse_id: self.last_sse_id.clone(),
+            messages: self
+                .messages
+                .iter()
+                .map(|m| StoredMessage {
+                    speaker: m.speaker.as_str().to_string(),
+                    content: m.content.clone(),
+                })
+                .collect(),
+        }
+    }
 }
 
 impl AppState {
@@ -209,27 +394,43 @@ impl AppState {
         let http_client = create_http_client();
         // Determine base URL (auto-discover via docker compose if requested)
         let env_base = std::env::var("MAGENT2_BASE_URL").unwrap_or_else(|_| "auto".to_string());
-        let base_url = if env_base.to_lowercase() == "auto" {
+        let discovered_base = if env_base.to_lowercase() == "auto" {
             discover_base_url()
         } else {
             env_base
         };
+        let endpoints = EndpointManager::load(discovered_base);
+        let base_url = endpoints.active_url();
+
+        let dock = DockLayout::load();
+
+        let store = SessionStore::open().ok();
+        let mut sessions: Vec<ChatSession> = store
+            .as_ref()
+            .and_then(|store| store.load_all().ok())
+            .filter(|stored| !stored.is_empty())
+            .map(|stored| stored.into_iter().map(ChatSession::from_stored).collect())
+            .unwrap_or_else(|| vec![ChatSession::new("Chat 1".to_string())]);
+
+        // Reconnect any restored session that has a live conversation, resuming
+        // from its last seen SSE id instead of starting cold.
+        for (idx, session) in sessions.iter_mut().enumerate() {
+            if let Some(conv) = session.conversation_id.clone() {
+                let handle = spawn_sse_task(
+                    base_url.clone(),
+                    idx,
+                    session.gen,
+                    tx.clone(),
+                    conv,
+                    session.last_sse_id.clone(),
+                    http_client.clone(),
+                );
+                session.stream_task = Some(handle);
+            }
+        }
 
         Self {
-            sessions: vec![ChatSession {
-                title: "Chat 1".to_string(),
-                messages: Vec::new(),
-                input: String::new(),
-                gen: 0,
-                last_sse_id: None,
-                scroll: 0,
-                max_scroll: 0,
-                viewport_height: 0,
-                follow: true,
-                stream_task: None,
-                conversation_id: None,
-                busy: None,
-            }],
+            sessions,
             active: 0,
             rx,
             tx,
@@ -240,21 +441,84 @@ impl AppState {
             show_conversations: false,
             conversations: Vec::new(),
             conversations_selected: 0,
-            show_agents: false,
+            show_agents: dock.is_visible(Pane::Agents),
             agents: Vec::new(),
             agents_last_fetch: None,
             agents_error: None,
-            show_graph: false,
+            agents_selected: 0,
+            agents_detail: false,
+            show_graph: dock.is_visible(Pane::Graph),
             graph: None,
             graph_for_conversation: None,
             graph_last_fetch: None,
             graph_error: None,
-            focus: FocusTarget::Input,
+            graph_selected: None,
+            graph_pinned: false,
+            focus: initial_focus(&dock),
+            tabs_area: Rect::default(),
+            chat_area: Rect::default(),
+            conversations_area: None,
+            input_area: Rect::default(),
             http_client,
+            keymap: Keymap::load(),
+            show_help: false,
+            notifier: Notifier::from_env(),
+            store,
+            webhook: Webhook::from_env(),
+            show_palette: false,
+            palette_query: String::new(),
+            palette_selected: 0,
+            palette_entries: Vec::new(),
+            token_meter: TokenMeter::from_env(),
+            show_events: dock.is_visible(Pane::Events),
+            events_log: VecDeque::new(),
+            events_paused: false,
+            events_filter: String::new(),
+            events_selected: 0,
+            history: CommandHistory::load(),
+            show_history_search: false,
+            history_query: String::new(),
+            history_skip: 0,
+            dock,
+            endpoints,
+            show_endpoints: false,
+            endpoints_selected: 0,
         }
     }
 }
 
+/// Map a persisted `DockLayout`'s last-focused pane back to a `FocusTarget`,
+/// falling back to `Input` if that pane isn't (or is no longer) visible.
+fn initial_focus(dock: &DockLayout) -> FocusTarget {
+    match dock.focus {
+        Some(Pane::Agents) if dock.is_visible(Pane::Agents) => FocusTarget::Agents,
+        Some(Pane::Graph) if dock.is_visible(Pane::Graph) => FocusTarget::Graph,
+        Some(Pane::Events) if dock.is_visible(Pane::Events) => FocusTarget::Events,
+        _ => FocusTarget::Input,
+    }
+}
+
+/// Build the static action entries plus one entry per known conversation,
+/// in the order the command palette should list them before ranking.
+fn build_palette_entries(conversations: &[String]) -> Vec<PaletteEntry> {
+    let mut entries = vec![
+        PaletteEntry::action("Clear chat", Action::ClearChat),
+        PaletteEntry::action("Clear input", Action::ClearInput),
+        PaletteEntry::action("Toggle conversations", Action::ToggleConversations),
+        PaletteEntry::action("Refresh conversations", Action::RefreshConversations),
+        PaletteEntry::action("Toggle agents panel", Action::ToggleAgents),
+        PaletteEntry::action("Toggle graph panel", Action::ToggleGraph),
+        PaletteEntry::action("Toggle event inspector", Action::ToggleEvents),
+        PaletteEntry::action("Switch endpoint", Action::ToggleEndpoints),
+        PaletteEntry::action("Toggle help", Action::ToggleHelp),
+        PaletteEntry::action("Next session", Action::NextSession),
+        PaletteEntry::action("New session", Action::NewSession),
+        PaletteEntry::action("Quit", Action::Quit),
+    ];
+    entries.extend(conversations.iter().cloned().map(PaletteEntry::conversation));
+    entries
+}
+
 // Minimal SSE JSON payload structure is handled dynamically in handle_sse_line
 
 #[tokio::main]
@@ -284,9 +548,35 @@ async fn main() -> std::io::Result<()> {
                 Err(_) => false,
             };
             app.gateway_ok = ok;
+            if ok {
+                app.endpoints.record_success();
+            } else {
+                let _ = app.endpoints.record_failure();
+            }
             last_health = Instant::now();
         }
         while let Ok(evt) = app.rx.try_recv() {
+            if !matches!(evt, UiEvent::StreamError { .. }) {
+                app.endpoints.record_success();
+            }
+            if !app.events_paused {
+                let (kind, idx, gen, payload) = describe_ui_event(&evt);
+                let conversation_id = app
+                    .sessions
+                    .get(idx)
+                    .and_then(|s| s.conversation_id.clone());
+                app.events_log.push_back(LoggedEvent {
+                    at: Instant::now(),
+                    idx,
+                    gen,
+                    conversation_id,
+                    kind,
+                    payload,
+                });
+                if app.events_log.len() > EVENTS_LOG_CAPACITY {
+                    app.events_log.pop_front();
+                }
+            }
             match evt {
                 UiEvent::User { idx, gen, text } => {
                     if let Some(session) = app.sessions.get_mut(idx) {
@@ -318,10 +608,42 @@ async fn main() -> std::io::Result<()> {
                     if let Some(session) = app.sessions.get_mut(idx) {
                         if gen == session.gen {
                             match status.as_str() {
-                                "succeeded" => session.set_busy(BusyReason::WaitingResponse),
-                                "failed" => session.set_busy(BusyReason::Error {
-                                    message: format!("Tool {} failed", name),
-                                }),
+                                "succeeded" => {
+                                    session.set_busy(BusyReason::WaitingResponse);
+                                    app.webhook.send(
+                                        &app.http_client,
+                                        WebhookEvent {
+                                            conversation_id: session.conversation_id.clone(),
+                                            session_title: session.title.clone(),
+                                            event: "tool_step",
+                                            tool_name: Some(name.clone()),
+                                            status: Some(status.clone()),
+                                            summary: summary.clone(),
+                                        },
+                                    );
+                                }
+                                "failed" => {
+                                    session.set_busy(BusyReason::Error {
+                                        message: format!("Tool {} failed", name),
+                                    });
+                                    app.notifier.notify_background(
+                                        idx,
+                                        app.active,
+                                        &session.title,
+                                        &format!("Tool {} failed", name),
+                                    );
+                                    app.webhook.send(
+                                        &app.http_client,
+                                        WebhookEvent {
+                                            conversation_id: session.conversation_id.clone(),
+                                            session_title: session.title.clone(),
+                                            event: "tool_step",
+                                            tool_name: Some(name.clone()),
+                                            status: Some(status.clone()),
+                                            summary: summary.clone(),
+                                        },
+                                    );
+                                }
                                 other => {
                                     session.set_busy(BusyReason::Tool {
                                         name,
@@ -360,23 +682,30 @@ async fn main() -> std::io::Result<()> {
                     if let Some(session) = app.sessions.get_mut(idx) {
                         if gen == session.gen {
                             session.clear_busy();
+                            if idx != app.active {
+                                session.unread = true;
+                            }
+                            let title = session.title.clone();
                             // Replace the last model message content with the final text
                             // or create it if it doesn't exist yet.
                             if let Some(last) = session.messages.last_mut() {
                                 if matches!(last.speaker, Speaker::Model) {
-                                    last.content = text;
+                                    last.content = text.clone();
                                 } else {
                                     session.messages.push(Message {
                                         speaker: Speaker::Model,
-                                        content: text,
+                                        content: text.clone(),
                                     });
                                 }
                             } else {
                                 session.messages.push(Message {
                                     speaker: Speaker::Model,
-                                    content: text,
+                                    content: text.clone(),
                                 });
                             }
+                            app.notifier
+                                .notify_background(idx, app.active, &title, &text);
+                            session.notified_output_gen = Some(gen);
                         }
                     }
                 }
@@ -390,7 +719,40 @@ async fn main() -> std::io::Result<()> {
                 UiEvent::StreamError { idx, gen, message } => {
                     if let Some(session) = app.sessions.get_mut(idx) {
                         if gen == session.gen {
-                            session.set_busy(BusyReason::Error { message });
+                            app.notifier
+                                .notify_background(idx, app.active, &session.title, &message);
+                            app.webhook.send(
+                                &app.http_client,
+                                WebhookEvent {
+                                    conversation_id: session.conversation_id.clone(),
+                                    session_title: session.title.clone(),
+                                    event: "stream_error",
+                                    tool_name: None,
+                                    status: None,
+                                    summary: Some(message.clone()),
+                                },
+                            );
+                            if let Some(conv) = session.conversation_id.clone() {
+                                let (attempt, backoff) = app.endpoints.record_failure();
+                                session.set_busy(BusyReason::Reconnecting { attempt });
+                                let resume_id = session.last_sse_id.clone();
+                                let tx = app.tx.clone();
+                                let client = app.http_client.clone();
+                                let base = app.base_url.clone();
+                                // Delay inside the spawned task (rather than nesting a second
+                                // spawn) so the handle we store is the one guarding the actual
+                                // SSE stream, letting switch_endpoint and friends abort it.
+                                let handle = tokio::spawn(async move {
+                                    tokio::time::sleep(backoff).await;
+                                    sse::spawn_unified_sse_task(
+                                        base, conv, resume_id, idx, gen, tx, client,
+                                    )
+                                    .await
+                                });
+                                session.stream_task = Some(handle);
+                            } else {
+                                session.set_busy(BusyReason::Error { message });
+                            }
                         }
                     }
                 }
@@ -398,6 +760,35 @@ async fn main() -> std::io::Result<()> {
                     if let Some(session) = app.sessions.get_mut(idx) {
                         if gen == session.gen {
                             session.clear_busy();
+                            if idx != app.active {
+                                session.unread = true;
+                            }
+                            // ModelOutput already notified for this generation when the run
+                            // ended with a final text reply; only notify here for runs that
+                            // closed without one (e.g. tool-only output).
+                            if session.notified_output_gen != Some(gen) {
+                                if let Some(last) = session.messages.last() {
+                                    if matches!(last.speaker, Speaker::Model) {
+                                        app.notifier.notify_background(
+                                            idx,
+                                            app.active,
+                                            &session.title,
+                                            &last.content,
+                                        );
+                                    }
+                                }
+                            }
+                            app.webhook.send(
+                                &app.http_client,
+                                WebhookEvent {
+                                    conversation_id: session.conversation_id.clone(),
+                                    session_title: session.title.clone(),
+                                    event: "stream_closed",
+                                    tool_name: None,
+                                    status: None,
+                                    summary: None,
+                                },
+                            );
                         }
                     }
                 }
@@ -413,6 +804,7 @@ async fn main() -> std::io::Result<()> {
                 match fetch_agents(&base_url, &app.http_client).await {
                     Ok(rows) => {
                         app.agents = rows;
+                        app.agents_selected = app.agents_selected.min(app.agents.len().saturating_sub(1));
                         app.agents_error = None;
                     }
                     Err(err) => {
@@ -422,7 +814,7 @@ async fn main() -> std::io::Result<()> {
                 app.agents_last_fetch = Some(Instant::now());
             }
         }
-        if app.show_graph {
+        if app.show_graph && !app.graph_pinned {
             let conversation_id = app
                 .sessions
                 .get(app.active)
@@ -472,177 +864,843 @@ async fn main() -> std::io::Result<()> {
                 }
                 CEvent::Paste(pasted) => {
                     if let Some(session) = app.sessions.get_mut(app.active) {
-                        session.input.push_str(&pasted);
+                        session.input.insert_str(&pasted);
                     }
                 }
                 CEvent::Resize(_, _) => {
                     // Trigger a redraw on next loop iteration (no-op; draw happens each loop)
                 }
+                CEvent::Mouse(mouse_event) => {
+                    handle_mouse_event(mouse_event, &mut app);
+                }
                 _ => {}
             }
         }
     }
 
+    if let Some(store) = app.store.as_mut() {
+        let stored: Vec<StoredSession> = app.sessions.iter().map(ChatSession::to_stored).collect();
+        let _ = store.replace_all(&stored);
+    }
+
     disable_terminal_features()?;
     ratatui::restore();
     Ok(())
 }
 
 async fn handle_key_event(key: KeyEvent, app: &mut AppState) -> bool {
+    if app.show_palette {
+        return handle_palette_key(key, app).await;
+    }
+    if app.show_history_search {
+        return handle_history_search_key(key, app);
+    }
+    if app.show_endpoints {
+        return handle_endpoints_key(key, app).await;
+    }
+    if app.agents_detail {
+        return handle_agent_detail_key(key, app).await;
+    }
     if !app.show_conversations && matches!(app.focus, FocusTarget::Conversations) {
         app.focus = FocusTarget::Input;
     }
+    if !app.show_agents && matches!(app.focus, FocusTarget::Agents) {
+        app.focus = FocusTarget::Input;
+        app.agents_detail = false;
+    }
+    if !app.show_graph && matches!(app.focus, FocusTarget::Graph) {
+        app.focus = FocusTarget::Input;
+    }
+    if !app.show_events && matches!(app.focus, FocusTarget::Events) {
+        app.focus = FocusTarget::Input;
+    }
+    // Line-editing chords take priority over the keymap while the input box
+    // has focus (e.g. Ctrl+A moves the cursor home here instead of toggling
+    // the agents panel), so they're checked before general action resolution.
+    if matches!(app.focus, FocusTarget::Input) && handle_input_editing_key(key, app) {
+        return false;
+    }
+    // Likewise, pause/clear chords take priority over the keymap while the
+    // events panel has focus (e.g. Ctrl+L clears the log here instead of
+    // clearing the chat), so the typed filter can use every other key.
+    if matches!(app.focus, FocusTarget::Events) && handle_events_panel_key(key, app) {
+        return false;
+    }
+    if let Some(action) = app.keymap.resolve(key) {
+        return perform_action(action, app).await;
+    }
     match key.code {
         KeyCode::Char(c) => {
-            // Handle control combos first
-            if key.modifiers.contains(event::KeyModifiers::CONTROL) {
-                match c {
-                    'l' => {
-                        if let Some(session) = app.sessions.get_mut(app.active) {
-                            session.messages.clear();
-                            session.scroll = 0;
-                            session.max_scroll = 0;
-                            session.follow = true;
-                        }
-                        return false;
-                    }
-                    'u' => {
-                        if let Some(session) = app.sessions.get_mut(app.active) {
-                            session.input.clear();
-                        }
-                        return false;
-                    }
-                    'c' => {
-                        app.show_conversations = !app.show_conversations;
-                        if app.show_conversations {
-                            let list = fetch_conversations(&app.base_url, &app.http_client).await;
-                            app.conversations = list;
-                            app.conversations_selected = 0;
-                            app.focus = FocusTarget::Conversations;
-                        } else {
-                            app.focus = FocusTarget::Input;
-                        }
-                        return false;
-                    }
-                    'r' => {
-                        if app.show_conversations {
-                            let list = fetch_conversations(&app.base_url, &app.http_client).await;
-                            app.conversations = list;
-                            if app.conversations_selected >= app.conversations.len() {
-                                if app.conversations.is_empty() {
-                                    app.conversations_selected = 0;
-                                } else {
-                                    app.conversations_selected =
-                                        app.conversations.len().saturating_sub(1);
-                                }
-                            }
-                        }
-                        return false;
+            if matches!(app.focus, FocusTarget::Events) {
+                app.events_filter.push(c);
+                app.events_selected = 0;
+            } else {
+                // Default: append to input
+                if !matches!(app.focus, FocusTarget::Input) {
+                    app.focus = FocusTarget::Input;
+                }
+                if let Some(session) = app.sessions.get_mut(app.active) {
+                    session.input.insert_char(c);
+                }
+            }
+        }
+        KeyCode::Backspace => {
+            if matches!(app.focus, FocusTarget::Events) {
+                app.events_filter.pop();
+                app.events_selected = 0;
+            } else if let Some(session) = app.sessions.get_mut(app.active) {
+                session.input.backspace();
+            }
+        }
+        KeyCode::Left => {
+            if matches!(app.focus, FocusTarget::Graph) {
+                move_graph_selection(app, NavDirection::Left);
+            } else if matches!(app.focus, FocusTarget::Input) {
+                if let Some(session) = app.sessions.get_mut(app.active) {
+                    session.input.move_left();
+                }
+            }
+        }
+        KeyCode::Right => {
+            if matches!(app.focus, FocusTarget::Graph) {
+                move_graph_selection(app, NavDirection::Right);
+            } else if matches!(app.focus, FocusTarget::Input) {
+                if let Some(session) = app.sessions.get_mut(app.active) {
+                    session.input.move_right();
+                }
+            }
+        }
+        KeyCode::Home => {
+            if matches!(app.focus, FocusTarget::Input) {
+                if let Some(session) = app.sessions.get_mut(app.active) {
+                    session.input.move_home();
+                }
+            } else if matches!(app.focus, FocusTarget::Agents) {
+                app.agents_selected = 0;
+            }
+        }
+        KeyCode::BackTab => {
+            app.focus = next_focus(app);
+        }
+        KeyCode::Up => {
+            if app.show_conversations && matches!(app.focus, FocusTarget::Conversations) {
+                if app.conversations_selected > 0 {
+                    app.conversations_selected -= 1;
+                }
+            } else if matches!(app.focus, FocusTarget::Agents) {
+                if app.agents_selected > 0 {
+                    app.agents_selected -= 1;
+                }
+            } else if matches!(app.focus, FocusTarget::Graph) {
+                move_graph_selection(app, NavDirection::Up);
+            } else if matches!(app.focus, FocusTarget::Events) {
+                if app.events_selected > 0 {
+                    app.events_selected -= 1;
+                }
+            } else if matches!(app.focus, FocusTarget::Input)
+                && app
+                    .sessions
+                    .get(app.active)
+                    .is_some_and(|s| s.input.cursor_on_first_line())
+            {
+                if let Some(session) = app.sessions.get_mut(app.active) {
+                    session.input.recall_prev(&app.history);
+                }
+            } else if let Some(session) = app.sessions.get_mut(app.active) {
+                if session.scroll > 0 {
+                    session.scroll -= 1;
+                }
+                session.follow = session.scroll == session.max_scroll;
+            }
+        }
+        KeyCode::Down => {
+            if app.show_conversations && matches!(app.focus, FocusTarget::Conversations) {
+                let max = app.conversations.len().saturating_sub(1);
+                if app.conversations_selected < max {
+                    app.conversations_selected += 1;
+                }
+            } else if matches!(app.focus, FocusTarget::Agents) {
+                let max = app.agents.len().saturating_sub(1);
+                if app.agents_selected < max {
+                    app.agents_selected += 1;
+                }
+            } else if matches!(app.focus, FocusTarget::Graph) {
+                move_graph_selection(app, NavDirection::Down);
+            } else if matches!(app.focus, FocusTarget::Events) {
+                let max = filtered_events(app).len().saturating_sub(1);
+                if app.events_selected < max {
+                    app.events_selected += 1;
+                }
+            } else if matches!(app.focus, FocusTarget::Input)
+                && app
+                    .sessions
+                    .get(app.active)
+                    .is_some_and(|s| s.input.cursor_on_last_line())
+            {
+                if let Some(session) = app.sessions.get_mut(app.active) {
+                    session.input.recall_next(&app.history);
+                }
+            } else if let Some(session) = app.sessions.get_mut(app.active) {
+                let new_scroll = session.scroll.saturating_add(1).min(session.max_scroll);
+                session.scroll = new_scroll;
+                session.follow = session.scroll == session.max_scroll;
+            }
+        }
+        KeyCode::PageUp => {
+            if app.show_conversations && matches!(app.focus, FocusTarget::Conversations) {
+                let dec = app.conversations_selected.saturating_sub(10);
+                app.conversations_selected = dec;
+            } else if matches!(app.focus, FocusTarget::Agents) {
+                app.agents_selected = app.agents_selected.saturating_sub(10);
+            } else if let Some(session) = app.sessions.get_mut(app.active) {
+                session.scroll = session.scroll.saturating_sub(10);
+                session.follow = session.scroll == session.max_scroll;
+            }
+        }
+        KeyCode::PageDown => {
+            if app.show_conversations && matches!(app.focus, FocusTarget::Conversations) {
+                let max = app.conversations.len().saturating_sub(1);
+                app.conversations_selected = (app.conversations_selected + 10).min(max);
+            } else if matches!(app.focus, FocusTarget::Agents) {
+                let max = app.agents.len().saturating_sub(1);
+                app.agents_selected = (app.agents_selected + 10).min(max);
+            } else if let Some(session) = app.sessions.get_mut(app.active) {
+                let new_scroll = session.scroll.saturating_add(10).min(session.max_scroll);
+                session.scroll = new_scroll;
+                session.follow = session.scroll == session.max_scroll;
+            }
+        }
+        KeyCode::End => {
+            if matches!(app.focus, FocusTarget::Input) {
+                if let Some(session) = app.sessions.get_mut(app.active) {
+                    session.input.move_end();
+                }
+            } else if matches!(app.focus, FocusTarget::Agents) {
+                app.agents_selected = app.agents.len().saturating_sub(1);
+            } else if let Some(session) = app.sessions.get_mut(app.active) {
+                session.scroll = session.max_scroll;
+                session.follow = true;
+            }
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Handle Emacs-style line-editing chords (`Ctrl+A`/`Ctrl+E` for
+/// home/end, `Alt+B`/`Alt+F` for word-wise movement, `Ctrl+W` to delete the
+/// previous word, `Ctrl+K`/`Ctrl+U` to kill to end/start of line) while the
+/// input box has focus. Returns `true` if the chord was recognized and
+/// consumed.
+fn handle_input_editing_key(key: KeyEvent, app: &mut AppState) -> bool {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let alt = key.modifiers.contains(KeyModifiers::ALT);
+    let Some(session) = app.sessions.get_mut(app.active) else {
+        return false;
+    };
+    match key.code {
+        KeyCode::Char('a') if ctrl => session.input.move_home(),
+        KeyCode::Char('e') if ctrl => session.input.move_end(),
+        KeyCode::Char('b') if alt => session.input.move_word_left(),
+        KeyCode::Char('f') if alt => session.input.move_word_right(),
+        KeyCode::Char('w') if ctrl => session.input.delete_word_left(),
+        KeyCode::Char('k') if ctrl => session.input.kill_to_end(),
+        KeyCode::Char('u') if ctrl => session.input.kill_to_start(),
+        KeyCode::Char('r') if ctrl => {
+            app.show_history_search = true;
+            app.history_query.clear();
+            app.history_skip = 0;
+            return true;
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Handle a key event while the reverse-search overlay (`Ctrl+R` from the
+/// input box) is open. Typed characters refine the subsequence query,
+/// repeated `Ctrl+R` steps to the next older match, Enter accepts the
+/// current match into the input, and Esc closes the overlay without
+/// touching the input. Any other key is ignored and the overlay stays open.
+fn handle_history_search_key(key: KeyEvent, app: &mut AppState) -> bool {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Char('r') if ctrl => {
+            let has_older = app
+                .history
+                .search_subsequence(&app.history_query, app.history_skip + 1)
+                .is_some();
+            app.history_skip += has_older as usize;
+        }
+        KeyCode::Esc => {
+            app.show_history_search = false;
+        }
+        KeyCode::Enter => {
+            let matched = app
+                .history
+                .search_subsequence(&app.history_query, app.history_skip)
+                .map(str::to_string);
+            app.show_history_search = false;
+            if let Some(text) = matched {
+                if let Some(session) = app.sessions.get_mut(app.active) {
+                    session.input.set_text(text);
+                }
+            }
+        }
+        KeyCode::Backspace => {
+            app.history_query.pop();
+            app.history_skip = 0;
+        }
+        KeyCode::Char(c) => {
+            app.history_query.push(c);
+            app.history_skip = 0;
+        }
+        _ => {}
+    }
+    true
+}
+
+/// Handle a key event while the endpoint picker overlay (`Ctrl+O`) is open.
+/// Up/Down move the selection, Enter switches the active endpoint and
+/// reconnects the active session's stream against it, and Esc closes the
+/// overlay without changing anything.
+async fn handle_endpoints_key(key: KeyEvent, app: &mut AppState) -> bool {
+    match key.code {
+        KeyCode::Esc => {
+            app.show_endpoints = false;
+        }
+        KeyCode::Up => {
+            app.endpoints_selected = app.endpoints_selected.saturating_sub(1);
+        }
+        KeyCode::Down => {
+            let max = app.endpoints.entries().len().saturating_sub(1);
+            if app.endpoints_selected < max {
+                app.endpoints_selected += 1;
+            }
+        }
+        KeyCode::Enter => {
+            app.show_endpoints = false;
+            switch_endpoint(app, app.endpoints_selected).await;
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Make `idx` the active gateway endpoint and, if the active session already
+/// has a live conversation, abort its current stream and re-issue the SSE
+/// subscription against the new endpoint from the stored `resume_id` so no
+/// events are lost across the switch.
+async fn switch_endpoint(app: &mut AppState, idx: usize) {
+    app.endpoints.set_active(idx);
+    app.base_url = app.endpoints.active_url();
+    let active = app.active;
+    if let Some(session) = app.sessions.get_mut(active) {
+        if let Some(conv) = session.conversation_id.clone() {
+            if let Some(handle) = session.stream_task.take() {
+                handle.abort();
+            }
+            let resume_id = session.last_sse_id.clone();
+            let handle = spawn_sse_task(
+                app.base_url.clone(),
+                active,
+                session.gen,
+                app.tx.clone(),
+                conv,
+                resume_id,
+                app.http_client.clone(),
+            );
+            session.stream_task = Some(handle);
+        }
+    }
+}
+
+/// Handle a key event while an agent's drill-down detail view is open
+/// (entered via Enter on the agents list). `g` loads the agent's most
+/// recent conversation directly into the graph panel, pinning it there so
+/// the background auto-sync to the active session's conversation doesn't
+/// immediately replace it; Esc returns to the collapsed list. Every other
+/// key is swallowed so list/global chords can't leak through while the
+/// detail is up.
+async fn handle_agent_detail_key(key: KeyEvent, app: &mut AppState) -> bool {
+    match key.code {
+        KeyCode::Esc => {
+            app.agents_detail = false;
+        }
+        KeyCode::Char('g') => {
+            let conv = app
+                .agents
+                .get(app.agents_selected)
+                .and_then(|agent| agent.recent_conversations.first().cloned());
+            if let Some(conv) = conv {
+                load_graph_for_conversation(app, conv, true).await;
+                app.agents_detail = false;
+                app.show_agents = false;
+                app.show_graph = true;
+                app.focus = FocusTarget::Graph;
+                app.dock.set_visible(Pane::Agents, false);
+                app.dock.set_visible(Pane::Graph, true);
+                app.dock.focus = Some(Pane::Graph);
+                app.dock.save();
+            }
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Fetch `conv`'s graph into the graph panel. When `pinned` is set, the
+/// per-frame auto-sync in the main loop (which otherwise keeps the graph
+/// panel following the active session's conversation) is suspended until
+/// the graph panel is next toggled.
+async fn load_graph_for_conversation(app: &mut AppState, conv: String, pinned: bool) {
+    app.graph = None;
+    app.graph_error = None;
+    app.graph_selected = None;
+    match fetch_graph(&app.base_url, &conv, &app.http_client).await {
+        Ok(graph) => {
+            app.graph_selected = graph.nodes.first().map(|n| n.id.clone());
+            app.graph = Some(graph);
+            app.graph_error = None;
+        }
+        Err(err) => {
+            app.graph = None;
+            app.graph_error = Some(err);
+        }
+    }
+    app.graph_for_conversation = Some(conv);
+    app.graph_last_fetch = Some(Instant::now());
+    app.graph_pinned = pinned;
+}
+
+/// Handle the event inspector's `Ctrl+P` (pause/resume capture) and
+/// `Ctrl+L` (clear the log) chords while it has focus. Returns `true` if
+/// the chord was recognized and consumed.
+fn handle_events_panel_key(key: KeyEvent, app: &mut AppState) -> bool {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Char('p') if ctrl => app.events_paused = !app.events_paused,
+        KeyCode::Char('l') if ctrl => {
+            app.events_log.clear();
+            app.events_selected = 0;
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Route a mouse event against the regions `render_ui` last recorded on
+/// `app`: wheel scroll over the chat area moves `session.scroll` exactly
+/// like the keyboard Up/Down arms, and a left click selects a tab, a
+/// conversations-panel row, or focuses the input box depending on where it
+/// landed.
+fn handle_mouse_event(mouse: MouseEvent, app: &mut AppState) {
+    let over_chat = rect_contains(app.chat_area, mouse.column, mouse.row);
+    match mouse.kind {
+        MouseEventKind::ScrollUp if over_chat => {
+            if let Some(session) = app.sessions.get_mut(app.active) {
+                if session.scroll > 0 {
+                    session.scroll -= 1;
+                }
+                session.follow = session.scroll == session.max_scroll;
+            }
+        }
+        MouseEventKind::ScrollDown if over_chat => {
+            if let Some(session) = app.sessions.get_mut(app.active) {
+                session.scroll = session.scroll.saturating_add(1).min(session.max_scroll);
+                session.follow = session.scroll == session.max_scroll;
+            }
+        }
+        MouseEventKind::Down(MouseButton::Left) => {
+            if rect_contains(app.tabs_area, mouse.column, mouse.row) {
+                if let Some(idx) = tab_index_at(app, mouse.column) {
+                    activate_session(app, idx);
+                }
+            } else if let Some(conv_area) = app.conversations_area.filter(|area| {
+                rect_contains(*area, mouse.column, mouse.row)
+            }) {
+                let row = mouse.row.saturating_sub(conv_area.y + 1) as usize;
+                if row < app.conversations.len() {
+                    app.conversations_selected = row;
+                }
+            } else if rect_contains(app.input_area, mouse.column, mouse.row) {
+                app.focus = FocusTarget::Input;
+            }
+        }
+        _ => {}
+    }
+}
+
+fn rect_contains(rect: Rect, x: u16, y: u16) -> bool {
+    x >= rect.x && x < rect.x.saturating_add(rect.width) && y >= rect.y && y < rect.y.saturating_add(rect.height)
+}
+
+/// Map a clicked column within `app.tabs_area` to the session tab under it,
+/// accounting for the `Tabs` widget's default border/padding/divider (`"
+/// <title> │ <title> │ ..."`).
+fn tab_index_at(app: &AppState, column: u16) -> Option<usize> {
+    let mut cursor = app.tabs_area.x.saturating_add(1);
+    for (idx, session) in app.sessions.iter().enumerate() {
+        let title_width = if session.unread || session.busy.is_some() {
+            session.title.width() + 2 // the appended " ●" marker
+        } else {
+            session.title.width()
+        };
+        let width = title_width as u16 + 2; // padding_left + title + padding_right
+        if column >= cursor && column < cursor + width {
+            return Some(idx);
+        }
+        cursor += width + 1; // + divider
+    }
+    None
+}
+
+/// Handle a key event while the command palette overlay is open. Typed
+/// characters refine the fuzzy query, Up/Down move the selection, Enter
+/// dispatches the highlighted entry through the same action layer
+/// `handle_key_event` uses, and Esc (or the palette chord itself) closes it
+/// without picking anything.
+async fn handle_palette_key(key: KeyEvent, app: &mut AppState) -> bool {
+    if let Some(Action::TogglePalette) = app.keymap.resolve(key) {
+        app.show_palette = false;
+        return false;
+    }
+    match key.code {
+        KeyCode::Esc => {
+            app.show_palette = false;
+        }
+        KeyCode::Enter => {
+            let target = {
+                let matches = palette::filter(&app.palette_query, &app.palette_entries);
+                matches.get(app.palette_selected).map(|entry| entry.target.clone())
+            };
+            app.show_palette = false;
+            match target {
+                Some(PaletteTarget::Action(action)) => return perform_action(action, app).await,
+                Some(PaletteTarget::Conversation(conversation_id)) => {
+                    open_conversation(app, conversation_id).await;
+                }
+                None => {}
+            }
+        }
+        KeyCode::Backspace => {
+            app.palette_query.pop();
+            app.palette_selected = 0;
+        }
+        KeyCode::Up => {
+            app.palette_selected = app.palette_selected.saturating_sub(1);
+        }
+        KeyCode::Down => {
+            let len = palette::filter(&app.palette_query, &app.palette_entries).len();
+            if app.palette_selected + 1 < len {
+                app.palette_selected += 1;
+            }
+        }
+        KeyCode::Char(c) => {
+            app.palette_query.push(c);
+            app.palette_selected = 0;
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Cycle `Shift+Tab` through whichever focus targets are currently visible:
+/// Input is always available, Conversations/Agents/Graph/Events only while
+/// their panels are open.
+fn next_focus(app: &AppState) -> FocusTarget {
+    let mut targets = vec![FocusTarget::Input];
+    if app.show_conversations {
+        targets.push(FocusTarget::Conversations);
+    }
+    if app.show_agents {
+        targets.push(FocusTarget::Agents);
+    }
+    if app.show_graph {
+        targets.push(FocusTarget::Graph);
+    }
+    if app.show_events {
+        targets.push(FocusTarget::Events);
+    }
+    let pos = targets.iter().position(|t| *t == app.focus).unwrap_or(0);
+    targets[(pos + 1) % targets.len()]
+}
+
+/// The dock pane that resize actions (`Ctrl+Up/Down`) apply to, i.e. the
+/// `Pane` backing the currently focused panel, if any.
+fn focused_pane(app: &AppState) -> Option<Pane> {
+    match app.focus {
+        FocusTarget::Agents => Some(Pane::Agents),
+        FocusTarget::Graph => Some(Pane::Graph),
+        FocusTarget::Events => Some(Pane::Events),
+        _ => None,
+    }
+}
+
+/// A panel's render function, as stored alongside its dock ratio.
+type PanelRenderer = fn(&mut Frame, Rect, &AppState);
+
+/// The render function for a dock pane, used to lay out `app.dock`'s
+/// visible panes in their persisted stacking order.
+fn pane_render_fn(pane: Pane) -> PanelRenderer {
+    match pane {
+        Pane::Agents => render_agents_panel,
+        Pane::Graph => render_graph_panel,
+        Pane::Events => render_events_panel,
+    }
+}
+
+/// Move the graph panel's selection in `dir` and store the result, using
+/// the active session's current graph data to recompute the layered order.
+fn move_graph_selection(app: &mut AppState, dir: graph_layout::NavDirection) {
+    let Some(graph) = &app.graph else {
+        return;
+    };
+    let nodes: Vec<graph_layout::Node> = graph
+        .nodes
+        .iter()
+        .map(|n| graph_layout::Node {
+            id: &n.id,
+            kind: &n.kind,
+        })
+        .collect();
+    let edges: Vec<graph_layout::Edge> = graph
+        .edges
+        .iter()
+        .map(|e| graph_layout::Edge {
+            from: &e.from,
+            to: &e.to,
+            count: e.count,
+        })
+        .collect();
+    let layers = graph_layout::layered_order(&nodes, &edges);
+    app.graph_selected = graph_layout::move_selection(&layers, app.graph_selected.as_deref(), dir);
+}
+
+/// Make `idx` the active session and clear its unread-completion marker,
+/// since the user is now looking at it.
+fn activate_session(app: &mut AppState, idx: usize) {
+    app.active = idx;
+    if let Some(session) = app.sessions.get_mut(idx) {
+        session.unread = false;
+    }
+}
+
+/// Switch the active session onto `conversation_id`: clear its scrollback,
+/// restart SSE streaming from the beginning, and return focus to the input
+/// box. Shared by the conversations panel's Enter-to-select and the command
+/// palette's conversation entries so both go through one code path.
+async fn open_conversation(app: &mut AppState, conversation_id: String) {
+    let idx = app.active;
+    if let Some(session) = app.sessions.get_mut(idx) {
+        session.gen = session.gen.saturating_add(1);
+        let gen = session.gen;
+        if let Some(handle) = session.stream_task.take() {
+            handle.abort();
+        }
+        session.last_sse_id = None;
+        session.messages.clear();
+        session.conversation_id = Some(conversation_id.clone());
+        let handle = spawn_sse_task(
+            app.base_url.clone(),
+            idx,
+            gen,
+            app.tx.clone(),
+            conversation_id,
+            None,
+            app.http_client.clone(),
+        );
+        if let Some(session) = app.sessions.get_mut(idx) {
+            session.stream_task = Some(handle);
+        }
+    }
+    app.show_conversations = false;
+    app.focus = FocusTarget::Input;
+}
+
+/// Dispatch a resolved [`Action`] from the keymap. Returns `true` when the
+/// application should exit.
+async fn perform_action(action: Action, app: &mut AppState) -> bool {
+    match action {
+        Action::ClearChat => {
+            if let Some(session) = app.sessions.get_mut(app.active) {
+                session.messages.clear();
+                session.scroll = 0;
+                session.max_scroll = 0;
+                session.follow = true;
+            }
+        }
+        Action::ClearInput => {
+            if let Some(session) = app.sessions.get_mut(app.active) {
+                session.input.clear();
+            }
+        }
+        Action::ToggleConversations => {
+            app.show_conversations = !app.show_conversations;
+            if app.show_conversations {
+                let list = fetch_conversations(&app.base_url, &app.http_client).await;
+                app.conversations = list;
+                app.conversations_selected = 0;
+                app.focus = FocusTarget::Conversations;
+            } else {
+                app.focus = FocusTarget::Input;
+            }
+        }
+        Action::RefreshConversations => {
+            if app.show_conversations {
+                let list = fetch_conversations(&app.base_url, &app.http_client).await;
+                app.conversations = list;
+                if app.conversations_selected >= app.conversations.len() {
+                    if app.conversations.is_empty() {
+                        app.conversations_selected = 0;
+                    } else {
+                        app.conversations_selected = app.conversations.len().saturating_sub(1);
                     }
-                    'a' => {
-                        app.show_agents = !app.show_agents;
-                        if app.show_agents {
-                            match fetch_agents(&app.base_url, &app.http_client).await {
-                                Ok(rows) => {
-                                    app.agents = rows;
-                                    app.agents_error = None;
-                                }
-                                Err(err) => {
-                                    app.agents_error = Some(err);
-                                }
-                            }
-                            app.agents_last_fetch = Some(Instant::now());
-                        }
-                        return false;
+                }
+            }
+        }
+        Action::ToggleAgents => {
+            app.show_agents = !app.show_agents;
+            if app.show_agents {
+                match fetch_agents(&app.base_url, &app.http_client).await {
+                    Ok(rows) => {
+                        app.agents = rows;
+                        app.agents_error = None;
                     }
-                    'g' => {
-                        app.show_graph = !app.show_graph;
-                        if app.show_graph {
-                            app.graph = None;
-                            app.graph_error = None;
-                            app.graph_for_conversation = None;
-                            app.graph_last_fetch = None;
-                            let conversation_id = app
-                                .sessions
-                                .get(app.active)
-                                .and_then(|s| s.conversation_id.clone());
-                            if let Some(conv) = conversation_id {
-                                match fetch_graph(&app.base_url, &conv, &app.http_client).await {
-                                    Ok(graph) => {
-                                        app.graph = Some(graph);
-                                        app.graph_error = None;
-                                    }
-                                    Err(err) => {
-                                        app.graph = None;
-                                        app.graph_error = Some(err);
-                                    }
-                                }
-                                app.graph_for_conversation = Some(conv);
-                                app.graph_last_fetch = Some(Instant::now());
-                            } else {
-                                app.graph_error = Some(
-                                    "No conversation yet. Send a message to populate graph."
-                                        .to_string(),
-                                );
-                            }
-                        } else {
-                            app.graph_error = None;
-                            app.graph_for_conversation = None;
-                            app.graph_last_fetch = None;
-                        }
-                        return false;
+                    Err(err) => {
+                        app.agents_error = Some(err);
                     }
-                    _ => {}
                 }
+                app.agents_last_fetch = Some(Instant::now());
+                app.agents_selected = 0;
+                app.agents_detail = false;
+                app.focus = FocusTarget::Agents;
+            } else {
+                app.agents_detail = false;
+                app.focus = FocusTarget::Input;
             }
-
-            // Default: append to input
-            if !matches!(app.focus, FocusTarget::Input) {
+            app.dock.set_visible(Pane::Agents, app.show_agents);
+            app.dock.focus = if app.show_agents { Some(Pane::Agents) } else { None };
+            app.dock.save();
+        }
+        Action::ToggleGraph => {
+            app.show_graph = !app.show_graph;
+            if app.show_graph {
+                app.graph = None;
+                app.graph_error = None;
+                app.graph_for_conversation = None;
+                app.graph_last_fetch = None;
+                app.graph_selected = None;
+                app.graph_pinned = false;
+                app.focus = FocusTarget::Graph;
+                let conversation_id = app
+                    .sessions
+                    .get(app.active)
+                    .and_then(|s| s.conversation_id.clone());
+                if let Some(conv) = conversation_id {
+                    load_graph_for_conversation(app, conv, false).await;
+                } else {
+                    app.graph_error = Some(
+                        "No conversation yet. Send a message to populate graph.".to_string(),
+                    );
+                }
+            } else {
+                app.graph_error = None;
+                app.graph_for_conversation = None;
+                app.graph_last_fetch = None;
+                app.graph_selected = None;
+                app.graph_pinned = false;
+                app.focus = FocusTarget::Input;
+            }
+            app.dock.set_visible(Pane::Graph, app.show_graph);
+            app.dock.focus = if app.show_graph { Some(Pane::Graph) } else { None };
+            app.dock.save();
+        }
+        Action::ToggleEvents => {
+            app.show_events = !app.show_events;
+            if app.show_events {
+                app.focus = FocusTarget::Events;
+                app.events_selected = 0;
+            } else {
                 app.focus = FocusTarget::Input;
             }
-            if let Some(session) = app.sessions.get_mut(app.active) {
-                session.input.push(c);
+            app.dock.set_visible(Pane::Events, app.show_events);
+            app.dock.focus = if app.show_events { Some(Pane::Events) } else { None };
+            app.dock.save();
+        }
+        Action::ToggleEndpoints => {
+            app.show_endpoints = !app.show_endpoints;
+            if app.show_endpoints {
+                app.endpoints_selected = app.endpoints.active;
             }
         }
-        KeyCode::Backspace => {
-            if let Some(session) = app.sessions.get_mut(app.active) {
-                session.input.pop();
+        Action::ToggleHelp => {
+            app.show_help = !app.show_help;
+        }
+        Action::TogglePalette => {
+            app.show_palette = !app.show_palette;
+            if app.show_palette {
+                let conversations = fetch_conversations(&app.base_url, &app.http_client).await;
+                app.palette_entries = build_palette_entries(&conversations);
+                app.palette_query.clear();
+                app.palette_selected = 0;
             }
         }
-        KeyCode::Enter => {
+        Action::GrowPane => {
+            if let Some(pane) = focused_pane(app) {
+                app.dock.grow(pane);
+                app.dock.save();
+            }
+        }
+        Action::ShrinkPane => {
+            if let Some(pane) = focused_pane(app) {
+                app.dock.shrink(pane);
+                app.dock.save();
+            }
+        }
+        Action::GrowSide => {
+            app.dock.grow_side();
+            app.dock.save();
+        }
+        Action::ShrinkSide => {
+            app.dock.shrink_side();
+            app.dock.save();
+        }
+        Action::ToggleDockDirection => {
+            app.dock.toggle_direction();
+            app.dock.save();
+        }
+        Action::NextSession => {
+            let next = (app.active + 1) % app.sessions.len();
+            activate_session(app, next);
+            app.focus = FocusTarget::Input;
+        }
+        Action::NewSession => {
+            let new_idx = app.sessions.len() + 1;
+            app.sessions
+                .push(ChatSession::new(format!("Chat {}", new_idx)));
+            activate_session(app, app.sessions.len() - 1);
+            app.focus = FocusTarget::Input;
+        }
+        Action::Quit => {
+            return true;
+        }
+        Action::Send => {
             if app.show_conversations && matches!(app.focus, FocusTarget::Conversations) {
                 // Switch to selected conversation and start SSE
                 if let Some(sel_id) = app.conversations.get(app.conversations_selected).cloned() {
-                    let idx = app.active;
-                    if let Some(session) = app.sessions.get_mut(idx) {
-                        session.gen = session.gen.saturating_add(1);
-                        let gen = session.gen;
-                        if let Some(h) = session.stream_task.take() {
-                            h.abort();
-                        }
-                        session.last_sse_id = None;
-                        session.messages.clear();
-                        session.conversation_id = Some(sel_id.clone());
-                        let handle = spawn_sse_task(
-                            app.base_url.clone(),
-                            idx,
-                            gen,
-                            app.tx.clone(),
-                            sel_id,
-                            None,
-                            app.http_client.clone(),
-                        );
-                        if let Some(s) = app.sessions.get_mut(idx) {
-                            s.stream_task = Some(handle);
-                        }
-                        app.show_conversations = false;
-                        app.focus = FocusTarget::Input;
-                    }
+                    open_conversation(app, sel_id).await;
+                }
+            } else if app.show_agents && matches!(app.focus, FocusTarget::Agents) {
+                // Drill into the selected agent's detail view
+                if app.agents_selected < app.agents.len() {
+                    app.agents_detail = true;
                 }
             } else {
                 let idx = app.active;
                 if let Some(session) = app.sessions.get_mut(idx) {
-                    let input = std::mem::take(&mut session.input);
+                    let input = session.input.submit();
+                    app.history.push(&input);
                     // Increment generation to invalidate any prior stream tasks for this session
                     session.gen = session.gen.saturating_add(1);
                     let gen = session.gen;
@@ -689,6 +1747,17 @@ async fn handle_key_event(key: KeyEvent, app: &mut AppState) -> bool {
                         });
                     } else {
                         session.set_busy(BusyReason::WaitingResponse);
+                        app.webhook.send(
+                            &app.http_client,
+                            WebhookEvent {
+                                conversation_id: session.conversation_id.clone(),
+                                session_title: session.title.clone(),
+                                event: "run_started",
+                                tool_name: None,
+                                status: None,
+                                summary: Some(input.clone()),
+                            },
+                        );
 
                         // Use unified SSE streaming
                         let handle = tokio::spawn(async move {
@@ -749,91 +1818,6 @@ async fn handle_key_event(key: KeyEvent, app: &mut AppState) -> bool {
                 }
             }
         }
-        KeyCode::Tab => {
-            app.active = (app.active + 1) % app.sessions.len();
-            app.focus = FocusTarget::Input;
-        }
-        KeyCode::BackTab => {
-            if app.show_conversations {
-                app.focus = match app.focus {
-                    FocusTarget::Input => FocusTarget::Conversations,
-                    FocusTarget::Conversations => FocusTarget::Input,
-                };
-            }
-        }
-        KeyCode::F(2) => {
-            let new_idx = app.sessions.len() + 1;
-            app.sessions.push(ChatSession {
-                title: format!("Chat {}", new_idx),
-                messages: Vec::new(),
-                input: String::new(),
-                gen: 0,
-                last_sse_id: None,
-                scroll: 0,
-                max_scroll: 0,
-                viewport_height: 0,
-                follow: true,
-                stream_task: None,
-                conversation_id: None, // new session starts blank; id allocated on first send
-                busy: None,
-            });
-            app.active = app.sessions.len() - 1;
-            app.focus = FocusTarget::Input;
-        }
-        KeyCode::Up => {
-            if app.show_conversations && matches!(app.focus, FocusTarget::Conversations) {
-                if app.conversations_selected > 0 {
-                    app.conversations_selected -= 1;
-                }
-            } else if let Some(session) = app.sessions.get_mut(app.active) {
-                if session.scroll > 0 {
-                    session.scroll -= 1;
-                }
-                session.follow = session.scroll == session.max_scroll;
-            }
-        }
-        KeyCode::Down => {
-            if app.show_conversations && matches!(app.focus, FocusTarget::Conversations) {
-                let max = app.conversations.len().saturating_sub(1);
-                if app.conversations_selected < max {
-                    app.conversations_selected += 1;
-                }
-            } else if let Some(session) = app.sessions.get_mut(app.active) {
-                let new_scroll = session.scroll.saturating_add(1).min(session.max_scroll);
-                session.scroll = new_scroll;
-                session.follow = session.scroll == session.max_scroll;
-            }
-        }
-        KeyCode::PageUp => {
-            if app.show_conversations && matches!(app.focus, FocusTarget::Conversations) {
-                let dec = app.conversations_selected.saturating_sub(10);
-                app.conversations_selected = dec;
-            } else if let Some(session) = app.sessions.get_mut(app.active) {
-                session.scroll = session.scroll.saturating_sub(10);
-                session.follow = session.scroll == session.max_scroll;
-            }
-        }
-        KeyCode::PageDown => {
-            if app.show_conversations && matches!(app.focus, FocusTarget::Conversations) {
-                let max = app.conversations.len().saturating_sub(1);
-                app.conversations_selected = (app.conversations_selected + 10).min(max);
-            } else if let Some(session) = app.sessions.get_mut(app.active) {
-                let new_scroll = session.scroll.saturating_add(10).min(session.max_scroll);
-                session.scroll = new_scroll;
-                session.follow = session.scroll == session.max_scroll;
-            }
-        }
-        KeyCode::End => {
-            if let Some(session) = app.sessions.get_mut(app.active) {
-                session.scroll = session.max_scroll;
-                session.follow = true;
-            }
-        }
-        // Ctrl+L and Ctrl+U handled in the Char(c) branch above
-        KeyCode::Esc => {
-            return true;
-        }
-        _ => {}
     }
     false
 }
@@ -911,7 +1895,7 @@ async fn fetch_agents(base_url: &str, client: &Client) -> Result<Vec<AgentRow>,
             name: item.name,
             active_runs: item.active_runs,
             last_seen,
-            recent_conversations: item.recent_conversations.len(),
+            recent_conversations: item.recent_conversations,
         });
     }
     out.sort_by(|a, b| {
@@ -1004,18 +1988,48 @@ fn render_ui(f: &mut Frame, app: &mut AppState) {
     let titles: Vec<Line> = app
         .sessions
         .iter()
-        .map(|s| Line::from(s.title.as_str()))
+        .map(|s| {
+            if s.unread || s.busy.is_some() {
+                Line::from(format!("{} ●", s.title))
+            } else {
+                Line::from(s.title.as_str())
+            }
+        })
         .collect();
     let status = if app.gateway_ok { "ok" } else { "down" };
+    let running = app.sessions.iter().filter(|s| s.busy.is_some()).count();
+    let done = app.sessions.iter().filter(|s| s.unread).count();
+    let endpoint = app.endpoints.active_endpoint();
+    let health = app.endpoints.active_status().health();
+    let mut sessions_title = format!(
+        "Sessions • Gateway: {} • {} [{}] (Ctrl+O switch)",
+        status,
+        endpoint.name,
+        health.label()
+    );
+    if !matches!(health, Health::Reachable) {
+        if let Some(since) = app.endpoints.active_status().since_last_ok() {
+            sessions_title.push_str(&format!(
+                " • last connected {} ago",
+                format_elapsed_compact(since)
+            ));
+        }
+    }
+    if running > 0 || done > 0 {
+        sessions_title.push_str(&format!(" • {} running, {} done", running, done));
+    }
     let tabs = Tabs::new(titles).select(app.active).block(
         Block::default()
             .borders(Borders::ALL)
-            .title(Line::from(format!("Sessions • Gateway: {}", status))),
+            .title(Line::from(sessions_title)),
     );
     f.render_widget(tabs, chunks[0]);
+    app.tabs_area = chunks[0];
+    app.input_area = chunks[2];
 
     // Optionally split middle area to show conversations list on the left
     let mut chat_area = chunks[1];
+    app.conversations_area = None;
     if app.show_conversations {
         let mid = Layout::default()
             .direction(Direction::Horizontal)
@@ -1053,21 +2067,28 @@ fn render_ui(f: &mut Frame, app: &mut AppState) {
         }
         let conv = Paragraph::new(conv_text).block(conv_block);
         f.render_widget(conv, mid[0]);
+        app.conversations_area = Some(mid[0]);
         chat_area = mid[1];
     }
 
     let mut side_area: Option<Rect> = None;
-    if app.show_agents || app.show_graph {
+    if app.show_help || app.show_agents || app.show_graph || app.show_events {
+        let side_percent = app.dock.side_percent;
         let split = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Min(40), Constraint::Length(38)])
+            .constraints([
+                Constraint::Percentage(100 - side_percent),
+                Constraint::Percentage(side_percent),
+            ])
             .split(chat_area);
         chat_area = split[0];
         side_area = Some(split[1]);
     }
+    app.chat_area = chat_area;
 
     if let Some(session) = app.sessions.get_mut(app.active) {
-        // Styled chat rendering with Markdown-aware content (basic lists/paragraphs)
+        // Styled chat rendering with Markdown-aware content (headings,
+        // emphasis, links, and fenced code blocks keep their structure).
         let mut lines: Vec<Line> = Vec::with_capacity(session.messages.len() + 1);
         for msg in &session.messages {
             let (label, style) = match msg.speaker {
@@ -1075,69 +2096,7 @@ fn render_ui(f: &mut Frame, app: &mut AppState) {
                 Speaker::Model => ("AI: ", Style::default().fg(Color::Yellow)),
                 Speaker::Tool => ("Tool: ", Style::default().fg(Color::Magenta)),
             };
-
-            let mut opts = MdOptions::empty();
-            opts.insert(MdOptions::ENABLE_TABLES);
-            opts.insert(MdOptions::ENABLE_FOOTNOTES);
-            let parser = MdParser::new_ext(&msg.content, opts);
-
-            let indent = " ".repeat(label.len());
-            let mut first_line = true;
-            let mut in_item = false;
-            let mut current = String::new();
-
-            let push_current = |lines: &mut Vec<Line>,
-                                first_line: &mut bool,
-                                current: &mut String,
-                                in_item: bool| {
-                if current.is_empty() {
-                    return;
-                }
-                let mut spans: Vec<Span> = Vec::new();
-                if *first_line {
-                    spans.push(Span::styled(label, style));
-                } else {
-                    spans.push(Span::raw(indent.clone()));
-                }
-                if in_item {
-                    spans.push(Span::raw("• "));
-                }
-                spans.push(Span::raw(current.clone()));
-                lines.push(Line::from(spans));
-                current.clear();
-                *first_line = false;
-            };
-
-            for ev in parser {
-                match ev {
-                    MdEvent::Start(Tag::Item) => {
-                        if !current.is_empty() {
-                            push_current(&mut lines, &mut first_line, &mut current, in_item);
-                        }
-                        in_item = true;
-                    }
-                    MdEvent::End(TagEnd::Item) => {
-                        push_current(&mut lines, &mut first_line, &mut current, in_item);
-                        in_item = false;
-                    }
-                    MdEvent::SoftBreak | MdEvent::HardBreak => {
-                        push_current(&mut lines, &mut first_line, &mut current, in_item);
-                    }
-                    MdEvent::Text(t) | MdEvent::Code(t) => {
-                        if !current.is_empty() {
-                            current.push(' ');
-                        }
-                        current.push_str(&t);
-                    }
-                    MdEvent::Start(Tag::Paragraph) | MdEvent::End(TagEnd::Paragraph) => {
-                        push_current(&mut lines, &mut first_line, &mut current, in_item);
-                    }
-                    _ => {}
-                }
-            }
-            if !current.is_empty() {
-                push_current(&mut lines, &mut first_line, &mut current, in_item);
-            }
+            lines.extend(rich_text::render_message(label, style, &msg.content));
         }
         let inner_width = chat_area.width.saturating_sub(2);
         let viewport_height = usize::from(chat_area.height.saturating_sub(2));
@@ -1162,6 +2121,17 @@ fn render_ui(f: &mut Frame, app: &mut AppState) {
         if !session.follow {
             chat_title.push_str(" — follow paused (End to resume)");
         }
+        if let Some(meter) = &mut app.token_meter {
+            let total = meter.total(session.messages.iter().map(|m| m.content.as_str()));
+            let percent = meter.percent_of_limit(total);
+            chat_title.push_str(&format!(
+                " • ~{}/{} tok [{}%] ({})",
+                total,
+                meter.limit(),
+                percent,
+                meter.encoding_name()
+            ));
+        }
 
         let mut paragraph = Paragraph::new(lines)
             .wrap(Wrap { trim: false })
@@ -1206,6 +2176,12 @@ fn render_ui(f: &mut Frame, app: &mut AppState) {
                     label.push_str(&format_elapsed_compact(elapsed));
                     label
                 }
+                BusyReason::Reconnecting { attempt } => format!(
+                    "{} reconnecting (attempt {}) • {}",
+                    spinner_display(busy.since),
+                    attempt,
+                    format_elapsed_compact(elapsed)
+                ),
                 BusyReason::Error { message } => {
                     format!("! {}", message)
                 }
@@ -1229,14 +2205,17 @@ fn render_ui(f: &mut Frame, app: &mut AppState) {
                     .add_modifier(Modifier::BOLD),
             );
         }
-        let input = Paragraph::new(session.input.clone()).block(input_block);
+        let input = Paragraph::new(session.input.as_str().to_string()).block(input_block);
         f.render_widget(input, chunks[2]);
 
         if matches!(app.focus, FocusTarget::Input) {
             let inner_x = chunks[2].x.saturating_add(1);
             let inner_y = chunks[2].y.saturating_add(1);
-            let (cursor_col, cursor_row) =
-                cursor_position(&session.input, chunks[2].width.saturating_sub(2));
+            let (cursor_col, cursor_row) = cursor_position(
+                session.input.as_str(),
+                session.input.cursor_chars(),
+                chunks[2].width.saturating_sub(2),
+            );
             let cursor_x = inner_x.saturating_add(cursor_col);
             let cursor_y = inner_y.saturating_add(cursor_row);
             if cursor_y < chunks[2].y.saturating_add(chunks[2].height) {
@@ -1246,66 +2225,313 @@ fn render_ui(f: &mut Frame, app: &mut AppState) {
     }
 
     if let Some(side) = side_area {
-        if app.show_agents && app.show_graph {
-            let halves = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-                .split(side);
-            render_agents_panel(f, halves[0], app);
-            render_graph_panel(f, halves[1], app);
-        } else if app.show_agents {
-            render_agents_panel(f, side, app);
-        } else if app.show_graph {
-            render_graph_panel(f, side, app);
+        if app.show_help {
+            render_help_panel(f, side, app);
+        } else {
+            let panels: Vec<(PanelRenderer, u16)> = app
+                .dock
+                .visible_panes()
+                .into_iter()
+                .map(|(pane, ratio)| (pane_render_fn(pane), ratio))
+                .collect();
+            if !panels.is_empty() {
+                let total: u32 = panels.iter().map(|(_, ratio)| u32::from(*ratio)).sum();
+                let constraints: Vec<Constraint> = panels
+                    .iter()
+                    .map(|(_, ratio)| Constraint::Ratio(u32::from(*ratio), total))
+                    .collect();
+                let direction = if app.dock.stack_horizontal {
+                    Direction::Horizontal
+                } else {
+                    Direction::Vertical
+                };
+                let areas = Layout::default()
+                    .direction(direction)
+                    .constraints(constraints)
+                    .split(side);
+                for ((panel_fn, _), panel_area) in panels.iter().zip(areas.iter()) {
+                    panel_fn(f, *panel_area, app);
+                }
+            }
+        }
+    }
+
+    if app.show_palette {
+        render_command_palette(f, size, app);
+    }
+    if app.show_history_search {
+        render_history_search_overlay(f, size, app);
+    }
+    if app.show_endpoints {
+        render_endpoints_overlay(f, size, app);
+    }
+}
+
+/// Render the reverse-search overlay as a floating box centered over the
+/// screen, on top of whatever else is showing.
+fn render_history_search_overlay(f: &mut Frame, screen: Rect, app: &AppState) {
+    let area = centered_rect(60, 20, screen);
+    f.render_widget(Clear, area);
+
+    let skip = app.history_skip;
+    let next_exists = app
+        .history
+        .search_subsequence(&app.history_query, skip + 1)
+        .is_some();
+    let matched = app.history.search_subsequence(&app.history_query, skip);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3)])
+        .split(area);
+
+    let query = Paragraph::new(app.history_query.as_str()).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(Line::from(format!(
+                "Reverse search (Ctrl+R {} • Enter accept • Esc cancel)",
+                if next_exists { "older" } else { "no older match" }
+            ))),
+    );
+    f.render_widget(query, chunks[0]);
+
+    let text = matched.unwrap_or("(no match)").to_string();
+    let result = Paragraph::new(text)
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::ALL).title(Line::from("Match")));
+    f.render_widget(result, chunks[1]);
+}
+
+/// Render the endpoint picker as a floating overlay centered over the whole
+/// screen: each configured endpoint with its health and, once down or
+/// degraded, how long since it last answered.
+fn render_endpoints_overlay(f: &mut Frame, screen: Rect, app: &AppState) {
+    let area = centered_rect(60, 50, screen);
+    f.render_widget(Clear, area);
+
+    let mut text = String::new();
+    for (i, endpoint) in app.endpoints.entries().iter().enumerate() {
+        if i == app.endpoints_selected {
+            text.push_str("> ");
+        } else {
+            text.push_str("  ");
+        }
+        text.push_str(&endpoint.name);
+        text.push_str(" — ");
+        text.push_str(&endpoint.url);
+        if let Some(status) = app.endpoints.status(i) {
+            text.push_str(" [");
+            text.push_str(status.health().label());
+            text.push(']');
+            if !matches!(status.health(), Health::Reachable) {
+                if let Some(since) = status.since_last_ok() {
+                    text.push_str(&format!(
+                        " • last connected {} ago",
+                        format_elapsed_compact(since)
+                    ));
+                }
+            }
+        }
+        if i == app.endpoints.active {
+            text.push_str(" (active)");
+        }
+        text.push('\n');
+    }
+    let list = Paragraph::new(text.trim_end().to_string())
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::ALL).title(Line::from(
+            "Endpoints (Ctrl+O toggle • Enter switch • Esc close)",
+        )));
+    f.render_widget(list, area);
+}
+
+/// Render the command palette as a floating overlay centered over the
+/// whole screen, on top of whatever else is showing.
+fn render_command_palette(f: &mut Frame, screen: Rect, app: &AppState) {
+    let area = centered_rect(60, 60, screen);
+    f.render_widget(Clear, area);
+
+    let matches = palette::filter(&app.palette_query, &app.palette_entries);
+    let selected = app.palette_selected.min(matches.len().saturating_sub(1));
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3)])
+        .split(area);
+
+    let query = Paragraph::new(app.palette_query.as_str()).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(Line::from("Command Palette (Ctrl+P toggle • Esc close)")),
+    );
+    f.render_widget(query, chunks[0]);
+
+    let mut text = String::new();
+    if matches.is_empty() {
+        text.push_str("(no matches)");
+    } else {
+        for (i, entry) in matches.iter().enumerate() {
+            if i == selected {
+                text.push_str("> ");
+            } else {
+                text.push_str("  ");
+            }
+            text.push_str(&entry.label);
+            text.push('\n');
         }
     }
+    let results = Paragraph::new(text.trim_end().to_string())
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::ALL).title(Line::from(
+            format!("Results ({})", matches.len()),
+        )));
+    f.render_widget(results, chunks[1]);
+}
+
+/// A `Rect` of `percent_x` × `percent_y` of `area`, centered within it.
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
 }
 
-fn cursor_position(input: &str, inner_width: u16) -> (u16, u16) {
+/// Translate a char-index cursor into the input box's (col, row), so the
+/// block cursor lands mid-line instead of always assuming end-of-string.
+fn cursor_position(input: &str, cursor_chars: usize, inner_width: u16) -> (u16, u16) {
     if inner_width == 0 {
         return (0, 0);
     }
     let available = inner_width as usize;
-    let last_line = input
+    let byte_idx = input
+        .char_indices()
+        .nth(cursor_chars)
+        .map(|(b, _)| b)
+        .unwrap_or(input.len());
+    let before_cursor = &input[..byte_idx];
+    let row = before_cursor.matches('\n').count();
+    let last_line = before_cursor
         .rsplit_once('\n')
         .map(|(_, tail)| tail)
-        .unwrap_or(input);
+        .unwrap_or(before_cursor);
     let raw_col = last_line.width();
     let capped_col = if available <= 1 { 0 } else { raw_col.min(available - 1) };
-    (capped_col.min(u16::MAX as usize) as u16, 0)
+    (
+        capped_col.min(u16::MAX as usize) as u16,
+        row.min(u16::MAX as usize) as u16,
+    )
 }
 
+/// Selectable agents panel. Normally shows a collapsed, truncated table with
+/// the selected row highlighted and Up/Down/PageUp/PageDown/Home/End
+/// navigation; pressing Enter on a row switches to a full-detail view for
+/// that agent (untruncated name, every recent conversation id, active runs,
+/// exact last-seen age) with a `g` chord to load its most recent
+/// conversation straight into the graph panel.
 fn render_agents_panel(f: &mut Frame, area: Rect, app: &AppState) {
-    let mut title = format!("Agents ({}) • Ctrl+A toggle", app.agents.len());
+    if app.agents_detail {
+        render_agent_detail_panel(f, area, app);
+        return;
+    }
+    let mut title = format!("Agents ({}) • Ctrl+A toggle • Enter for detail", app.agents.len());
     if app.agents_error.is_some() {
         title.push_str(" • last fetch error");
     }
     let mut text = String::new();
+    let mut header_lines = 0u16;
     if let Some(err) = &app.agents_error {
         text.push_str(&format!("! {}\n", err));
+        header_lines += 1;
     }
+    let mut selected_line = 0u16;
     if app.agents.is_empty() {
         text.push_str("No agent activity yet.");
     } else {
         let now = SystemTime::now();
-        text.push_str("Name             Runs  Last Seen           Recent\n");
-        text.push_str("---------------- ----- ------------------- ------\n");
-        for agent in &app.agents {
+        let selected = app.agents_selected.min(app.agents.len() - 1);
+        text.push_str("  Name             Runs  Last Seen           Recent\n");
+        text.push_str("  ---------------- ----- ------------------- ------\n");
+        header_lines += 2;
+        for (i, agent) in app.agents.iter().enumerate() {
+            if i == selected {
+                selected_line = header_lines + i as u16;
+            }
             let name = truncate_with_ellipsis(&agent.name, 16);
             let last_seen = agent
                 .last_seen
                 .and_then(|ts| now.duration_since(ts).ok())
                 .map(|dur| format!("{} ago", format_elapsed_compact(dur)))
                 .unwrap_or_else(|| "unknown".to_string());
+            text.push_str(if i == selected { "> " } else { "  " });
             text.push_str(&format!(
                 "{:<16} {:>4}  {:<19} {:>4}\n",
                 name,
                 agent.active_runs,
                 truncate_with_ellipsis(&last_seen, 19),
-                agent.recent_conversations
+                agent.recent_conversations.len()
             ));
         }
     }
+    let mut block = Block::default().borders(Borders::ALL).title(Line::from(title));
+    if matches!(app.focus, FocusTarget::Agents) {
+        block = block.border_style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        );
+    }
+    // Scroll so the selected row stays visible once the list overflows the
+    // pane's height, instead of scrolling off-screen with no indication.
+    let visible_rows = area.height.saturating_sub(2);
+    let scroll = selected_line.saturating_sub(visible_rows.saturating_sub(1));
+    let paragraph = Paragraph::new(text.trim_end().to_string())
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0))
+        .block(block);
+    f.render_widget(paragraph, area);
+}
+
+/// Full, untruncated detail view for the agent selected in
+/// `render_agents_panel`, entered via Enter.
+fn render_agent_detail_panel(f: &mut Frame, area: Rect, app: &AppState) {
+    let title = "Agent detail • g load graph • Esc back";
+    let text = match app.agents.get(app.agents_selected) {
+        Some(agent) => {
+            let last_seen = agent
+                .last_seen
+                .and_then(|ts| SystemTime::now().duration_since(ts).ok())
+                .map(|dur| format!("{}s ago", dur.as_secs()))
+                .unwrap_or_else(|| "unknown".to_string());
+            let mut out = format!(
+                "Name: {}\nActive runs: {}\nLast seen: {}\n\nRecent conversations ({}):\n",
+                agent.name,
+                agent.active_runs,
+                last_seen,
+                agent.recent_conversations.len()
+            );
+            if agent.recent_conversations.is_empty() {
+                out.push_str("  (none)\n");
+            } else {
+                for conv in &agent.recent_conversations {
+                    out.push_str(&format!("  {}\n", conv));
+                }
+            }
+            out
+        }
+        None => "No agent selected.".to_string(),
+    };
     let paragraph = Paragraph::new(text.trim_end().to_string())
         .wrap(Wrap { trim: false })
         .block(
@@ -1316,52 +2542,62 @@ fn render_agents_panel(f: &mut Frame, area: Rect, app: &AppState) {
     f.render_widget(paragraph, area);
 }
 
+fn render_help_panel(f: &mut Frame, area: Rect, app: &AppState) {
+    let mut text = String::new();
+    for (name, chord) in app.keymap.entries() {
+        text.push_str(&format!("{:<22} {}\n", name, chord));
+    }
+    let paragraph = Paragraph::new(text.trim_end().to_string())
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(Line::from("Keybindings • F1 toggle")),
+        );
+    f.render_widget(paragraph, area);
+}
+
 fn render_graph_panel(f: &mut Frame, area: Rect, app: &AppState) {
-    let mut title = String::from("Conversation Graph • Ctrl+G toggle");
+    let mut title = String::from("Conversation Graph • Ctrl+G toggle • arrows navigate");
     if let Some(conv) = app.graph_for_conversation.as_deref() {
         title.push_str(" • ");
         title.push_str(&truncate_with_ellipsis(conv, 18));
     }
-    let mut text = String::new();
+    if app.graph_pinned {
+        title.push_str(" • pinned");
+    }
+    let mut lines: Vec<Line<'static>> = Vec::new();
     if let Some(err) = &app.graph_error {
-        text.push_str(&format!("! {}\n", err));
+        lines.push(Line::from(format!("! {}", err)));
     }
     if let Some(graph) = &app.graph {
-        if graph.nodes.is_empty() && graph.edges.is_empty() {
-            text.push_str("No graph data yet.");
-        } else {
-            if !graph.nodes.is_empty() {
-                text.push_str("Nodes:\n");
-                for node in &graph.nodes {
-                    text.push_str(&format!(
-                        "- {} ({})\n",
-                        truncate_with_ellipsis(&node.id, 24),
-                        node.kind
-                    ));
-                }
-            }
-            if !graph.edges.is_empty() {
-                if !text.ends_with('\n') {
-                    text.push('\n');
-                }
-                text.push_str("Edges:\n");
-                for edge in &graph.edges {
-                    text.push_str(&format!(
-                        "- {} -> {} (x{})\n",
-                        truncate_with_ellipsis(&edge.from, 16),
-                        truncate_with_ellipsis(&edge.to, 16),
-                        edge.count
-                    ));
-                }
-                if graph.omitted_edges > 0 {
-                    text.push_str(&format!("(+{} more edges omitted)\n", graph.omitted_edges));
-                }
-            }
-        }
+        let nodes: Vec<graph_layout::Node> = graph
+            .nodes
+            .iter()
+            .map(|n| graph_layout::Node {
+                id: &n.id,
+                kind: &n.kind,
+            })
+            .collect();
+        let edges: Vec<graph_layout::Edge> = graph
+            .edges
+            .iter()
+            .map(|e| graph_layout::Edge {
+                from: &e.from,
+                to: &e.to,
+                count: e.count,
+            })
+            .collect();
+        lines.extend(graph_layout::render(
+            &nodes,
+            &edges,
+            graph.omitted_edges,
+            app.graph_selected.as_deref(),
+        ));
     } else if app.graph_error.is_none() {
-        text.push_str("Graph data not loaded yet.");
+        lines.push(Line::from("Graph data not loaded yet."));
     }
-    let paragraph = Paragraph::new(text.trim_end().to_string())
+    let paragraph = Paragraph::new(lines)
         .wrap(Wrap { trim: false })
         .block(
             Block::default()
@@ -1371,6 +2607,85 @@ fn render_graph_panel(f: &mut Frame, area: Rect, app: &AppState) {
     f.render_widget(paragraph, area);
 }
 
+/// Live SSE event inspector: a scrollable list of captured `UiEvent`s on the
+/// left (elapsed time, kind, session idx/gen), a pretty-printed detail view
+/// of the selected one on the right. Capture can be paused without
+/// affecting the chat stream, and a substring filter narrows the list.
+fn render_events_panel(f: &mut Frame, area: Rect, app: &AppState) {
+    let events = filtered_events(app);
+    let selected = app.events_selected.min(events.len().saturating_sub(1));
+    let now = Instant::now();
+
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(area);
+
+    let list_text = if events.is_empty() {
+        "(no events captured)".to_string()
+    } else {
+        let mut out = String::new();
+        for (i, event) in events.iter().enumerate() {
+            out.push_str(if i == selected { "> " } else { "  " });
+            out.push_str(&format!(
+                "{:>5} {:<12} #{}g{}\n",
+                format_elapsed_compact(now.saturating_duration_since(event.at)),
+                event.kind,
+                event.idx,
+                event.gen
+            ));
+        }
+        out
+    };
+    let mut list_title = format!(
+        "Events ({}/{}) • Ctrl+E toggle • Ctrl+P pause • Ctrl+L clear",
+        events.len(),
+        app.events_log.len()
+    );
+    if app.events_paused {
+        list_title.push_str(" • PAUSED");
+    }
+    if matches!(app.focus, FocusTarget::Events) {
+        list_title.push_str(" • [FOCUS]");
+    }
+    if !app.events_filter.is_empty() {
+        list_title.push_str(&format!(" • filter: {}", app.events_filter));
+    }
+    let mut list_block = Block::default()
+        .borders(Borders::ALL)
+        .title(Line::from(list_title));
+    if matches!(app.focus, FocusTarget::Events) {
+        list_block = list_block.border_style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        );
+    }
+    let list = Paragraph::new(list_text).block(list_block);
+    f.render_widget(list, panes[0]);
+
+    let detail_text = match events.get(selected) {
+        Some(event) => format!(
+            "kind: {}\nidx: {}\ngen: {}\nconversation_id: {}\nat: {} ago\n\n{}",
+            event.kind,
+            event.idx,
+            event.gen,
+            event.conversation_id.as_deref().unwrap_or("(none)"),
+            format_elapsed_compact(now.saturating_duration_since(event.at)),
+            event.payload
+        ),
+        None => "No event selected.".to_string(),
+    };
+    let detail = Paragraph::new(detail_text)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(Line::from("Detail")),
+        );
+    f.render_widget(detail, panes[1]);
+}
+
 fn truncate_with_ellipsis(input: &str, max_chars: usize) -> String {
     if max_chars == 0 {
         return String::new();
@@ -1435,16 +2750,18 @@ fn enable_terminal_features() -> io::Result<()> {
     execute!(
         io::stdout(),
         EnableBracketedPaste,
+        EnableMouseCapture,
         PushKeyboardEnhancementFlags(flags)
     )?;
     Ok(())
 }
 
 fn disable_terminal_features() -> io::Result<()> {
-    // Pop enhancement flags and disable bracketed paste; ignore errors
+    // Pop enhancement flags and disable bracketed paste/mouse capture; ignore errors
     let _ = execute!(
         io::stdout(),
         PopKeyboardEnhancementFlags,
+        DisableMouseCapture,
         DisableBracketedPaste
     );
     Ok(())
@@ -1490,6 +2807,22 @@ fn spinner_display(since: Instant) -> String {
     format!("{:<3}", frame)
 }
 
+/// Events from `app.events_log` whose kind or payload contains
+/// `app.events_filter` as a case-insensitive substring (empty filter keeps
+/// everything), oldest first.
+fn filtered_events(app: &AppState) -> Vec<&LoggedEvent> {
+    if app.events_filter.is_empty() {
+        return app.events_log.iter().collect();
+    }
+    let needle = app.events_filter.to_lowercase();
+    app.events_log
+        .iter()
+        .filter(|e| {
+            e.kind.to_lowercase().contains(&needle) || e.payload.to_lowercase().contains(&needle)
+        })
+        .collect()
+}
+
 fn format_elapsed_compact(dur: Duration) -> String {
     if dur.as_secs() >= 3600 {
         let hours = dur.as_secs() / 3600;
@@ -0,0 +1,53 @@
+/// Desktop notification subsystem, gated by the `MAGENT2_NOTIFY` env var.
+///
+/// Fires native OS notifications (via `notify-rust`) for events that
+/// happened in a session the user isn't currently looking at, so a
+/// background run finishing doesn't go unnoticed.
+pub struct Notifier {
+    enabled: bool,
+}
+
+const SNIPPET_CHARS: usize = 160;
+
+impl Notifier {
+    pub fn from_env() -> Self {
+        let enabled = std::env::var("MAGENT2_NOTIFY")
+            .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+            .unwrap_or(false);
+        Self { enabled }
+    }
+
+    /// Notify unless suppressed, either because the subsystem is disabled or
+    /// because `session_idx` is the session the user is currently focused on.
+    pub fn notify_background(
+        &self,
+        session_idx: usize,
+        active_idx: usize,
+        title: &str,
+        body: &str,
+    ) {
+        if !self.enabled || session_idx == active_idx {
+            return;
+        }
+        self.fire(title, body);
+    }
+
+    fn fire(&self, summary: &str, body: &str) {
+        let snippet = truncate_snippet(body);
+        // Best-effort: the user's desktop environment may not have a
+        // notification daemon running; failures are silently ignored.
+        let _ = notify_rust::Notification::new()
+            .summary(summary)
+            .body(&snippet)
+            .show();
+    }
+}
+
+fn truncate_snippet(text: &str) -> String {
+    let trimmed = text.trim();
+    let mut out: String = trimmed.chars().take(SNIPPET_CHARS).collect();
+    if trimmed.chars().count() > SNIPPET_CHARS {
+        out.push('…');
+    }
+    out
+}
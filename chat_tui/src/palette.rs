@@ -0,0 +1,130 @@
+use crate::keymap::Action;
+
+/// What selecting a palette entry should do.
+#[derive(Clone)]
+pub enum PaletteTarget {
+    Action(Action),
+    Conversation(String),
+}
+
+/// A single row in the command palette: a display label plus what happens
+/// when it's chosen.
+#[derive(Clone)]
+pub struct PaletteEntry {
+    pub label: String,
+    pub target: PaletteTarget,
+}
+
+impl PaletteEntry {
+    pub fn action(label: &str, action: Action) -> Self {
+        Self {
+            label: label.to_string(),
+            target: PaletteTarget::Action(action),
+        }
+    }
+
+    pub fn conversation(id: String) -> Self {
+        Self {
+            label: format!("Open conversation: {}", id),
+            target: PaletteTarget::Conversation(id),
+        }
+    }
+}
+
+/// Score `candidate` as a fuzzy subsequence match of `query`, rewarding
+/// contiguous runs and word-boundary starts so e.g. "tg" ranks "Toggle
+/// graph panel" above a coincidental scattered match. Returns `None` if
+/// `query` isn't a subsequence of `candidate` at all.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+    let q: Vec<char> = query.to_lowercase().chars().collect();
+    let c: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut qi = 0;
+    let mut score: i64 = 0;
+    let mut prev_match: Option<usize> = None;
+    for (ci, &ch) in c.iter().enumerate() {
+        if qi >= q.len() {
+            break;
+        }
+        if ch != q[qi] {
+            continue;
+        }
+        score += 1;
+        if prev_match.is_some() && prev_match == ci.checked_sub(1) {
+            score += 5;
+        }
+        if ci == 0 || matches!(c[ci - 1], ' ' | '_' | '-' | ':') {
+            score += 3;
+        }
+        prev_match = Some(ci);
+        qi += 1;
+    }
+    if qi == q.len() { Some(score) } else { None }
+}
+
+/// Rank `entries` against `query`, dropping non-matches, best match first.
+/// Re-run on every keystroke; the candidate lists here are small enough
+/// that there's no need to cache partial results.
+pub fn filter<'a>(query: &str, entries: &'a [PaletteEntry]) -> Vec<&'a PaletteEntry> {
+    let mut scored: Vec<(i64, &PaletteEntry)> = entries
+        .iter()
+        .filter_map(|entry| fuzzy_score(query, &entry.label).map(|score| (score, entry)))
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, entry)| entry).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score_empty_query_matches_everything() {
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+    }
+
+    #[test]
+    fn test_fuzzy_score_non_subsequence_is_none() {
+        assert_eq!(fuzzy_score("xyz", "Toggle graph panel"), None);
+    }
+
+    #[test]
+    fn test_fuzzy_score_contiguous_run_beats_scattered_match() {
+        let contiguous = fuzzy_score("ta", "xtab").unwrap();
+        let scattered = fuzzy_score("ta", "xtxa").unwrap();
+        assert!(contiguous > scattered);
+    }
+
+    #[test]
+    fn test_fuzzy_score_first_matched_char_alone_has_no_contiguous_bonus() {
+        // A single-character query can never form an adjacent pair; this
+        // guards against `prev_match == ci.checked_sub(1)` spuriously
+        // treating "no previous match" (None) as adjacent to index 0.
+        let at_start = fuzzy_score("t", "tab").unwrap();
+        let mid = fuzzy_score("t", "xat").unwrap();
+        // Only the word-boundary bonus (+3) should separate these, not a
+        // contiguous-run bonus (+5) on top of it.
+        assert_eq!(at_start, mid + 3);
+    }
+
+    #[test]
+    fn test_fuzzy_score_word_boundary_beats_mid_word() {
+        let boundary = fuzzy_score("g", "graph").unwrap();
+        let mid_word = fuzzy_score("g", "xgraph").unwrap();
+        assert!(boundary > mid_word);
+    }
+
+    #[test]
+    fn test_filter_drops_non_matches_and_ranks_best_first() {
+        let entries = vec![
+            PaletteEntry::action("Toggle graph panel", Action::ToggleGraph),
+            PaletteEntry::action("Toggle events panel", Action::ToggleEvents),
+        ];
+        // "tgr" is only a subsequence of "Toggle graph panel".
+        let results = filter("tgr", &entries);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].label, "Toggle graph panel");
+    }
+}
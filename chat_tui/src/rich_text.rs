@@ -0,0 +1,171 @@
+use pulldown_cmark::{CodeBlockKind, Event as MdEvent, Options as MdOptions, Parser as MdParser, Tag, TagEnd};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+use crate::highlight;
+
+/// Background used for fenced code block lines, distinct from every
+/// foreground color `highlight::highlight_line` hands out.
+const CODE_BG: Color = Color::Rgb(30, 30, 30);
+
+/// Render one message's Markdown body into styled `Line`s. `label` (styled
+/// `label_style`) prefixes the first line; continuation lines are indented
+/// to match its width. Headings, bold/italic emphasis, links, and fenced
+/// code blocks keep their structure instead of collapsing into flat prose.
+pub fn render_message(label: &str, label_style: Style, content: &str) -> Vec<Line<'static>> {
+    let mut opts = MdOptions::empty();
+    opts.insert(MdOptions::ENABLE_TABLES);
+    opts.insert(MdOptions::ENABLE_FOOTNOTES);
+    let parser = MdParser::new_ext(content, opts);
+
+    let indent = " ".repeat(label.len());
+    let mut lines: Vec<Line<'static>> = Vec::new();
+    let mut current: Vec<Span<'static>> = Vec::new();
+    let mut first_line = true;
+    let mut in_item = false;
+    let mut heading_marker: Option<String> = None;
+    let mut modifiers: Vec<Modifier> = Vec::new();
+    let mut link_urls: Vec<String> = Vec::new();
+    let mut code_lang: Option<String> = None;
+    let mut code_buffer = String::new();
+
+    let flush = |lines: &mut Vec<Line<'static>>,
+                 current: &mut Vec<Span<'static>>,
+                 first_line: &mut bool,
+                 in_item: bool,
+                 heading_marker: &Option<String>| {
+        if current.is_empty() && heading_marker.is_none() {
+            return;
+        }
+        let mut spans: Vec<Span<'static>> = Vec::with_capacity(current.len() + 2);
+        if *first_line {
+            spans.push(Span::styled(label.to_string(), label_style));
+        } else {
+            spans.push(Span::raw(indent.clone()));
+        }
+        if in_item {
+            spans.push(Span::raw("• "));
+        }
+        if let Some(marker) = heading_marker {
+            spans.push(Span::styled(
+                format!("{} ", marker),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+        }
+        spans.append(current);
+        lines.push(Line::from(spans));
+        *first_line = false;
+    };
+
+    let push_code_line = |lines: &mut Vec<Line<'static>>,
+                          first_line: &mut bool,
+                          lang: &str,
+                          code_line: &str| {
+        let mut spans: Vec<Span<'static>> = Vec::new();
+        if *first_line {
+            spans.push(Span::styled(label.to_string(), label_style));
+        } else {
+            spans.push(Span::raw(indent.clone()));
+        }
+        for (text, tok_style) in highlight::highlight_line(lang, code_line) {
+            spans.push(Span::styled(text, tok_style.bg(CODE_BG)));
+        }
+        lines.push(Line::from(spans));
+        *first_line = false;
+    };
+
+    for ev in parser {
+        match ev {
+            MdEvent::Start(Tag::CodeBlock(kind)) => {
+                flush(&mut lines, &mut current, &mut first_line, in_item, &heading_marker);
+                code_lang = Some(match kind {
+                    CodeBlockKind::Fenced(lang) => lang.to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                });
+                code_buffer.clear();
+            }
+            MdEvent::End(TagEnd::CodeBlock) => {
+                if let Some(lang) = code_lang.take() {
+                    // pulldown_cmark's Text events for a fenced block include
+                    // its trailing newline, so a plain split('\n') always
+                    // yields one spurious empty line at the end.
+                    for code_line in code_buffer.trim_end_matches('\n').split('\n') {
+                        push_code_line(&mut lines, &mut first_line, &lang, code_line);
+                    }
+                }
+                code_buffer.clear();
+            }
+            MdEvent::Start(Tag::Heading { level, .. }) => {
+                flush(&mut lines, &mut current, &mut first_line, in_item, &heading_marker);
+                heading_marker = Some("#".repeat(level as usize));
+                modifiers.push(Modifier::BOLD);
+            }
+            MdEvent::End(TagEnd::Heading(_)) => {
+                flush(&mut lines, &mut current, &mut first_line, in_item, &heading_marker);
+                heading_marker = None;
+                pop_modifier(&mut modifiers, Modifier::BOLD);
+            }
+            MdEvent::Start(Tag::Strong) => modifiers.push(Modifier::BOLD),
+            MdEvent::End(TagEnd::Strong) => pop_modifier(&mut modifiers, Modifier::BOLD),
+            MdEvent::Start(Tag::Emphasis) => modifiers.push(Modifier::ITALIC),
+            MdEvent::End(TagEnd::Emphasis) => pop_modifier(&mut modifiers, Modifier::ITALIC),
+            MdEvent::Start(Tag::Link { dest_url, .. }) => {
+                modifiers.push(Modifier::UNDERLINED);
+                link_urls.push(dest_url.to_string());
+            }
+            MdEvent::End(TagEnd::Link) => {
+                pop_modifier(&mut modifiers, Modifier::UNDERLINED);
+                if let Some(url) = link_urls.pop() {
+                    current.push(Span::styled(
+                        format!(" ({})", url),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+            }
+            MdEvent::Start(Tag::Item) => {
+                if !current.is_empty() {
+                    flush(&mut lines, &mut current, &mut first_line, in_item, &heading_marker);
+                }
+                in_item = true;
+            }
+            MdEvent::End(TagEnd::Item) => {
+                flush(&mut lines, &mut current, &mut first_line, in_item, &heading_marker);
+                in_item = false;
+            }
+            MdEvent::SoftBreak | MdEvent::HardBreak => {
+                flush(&mut lines, &mut current, &mut first_line, in_item, &heading_marker);
+            }
+            MdEvent::Text(t) | MdEvent::Code(t) => {
+                if code_lang.is_some() {
+                    code_buffer.push_str(&t);
+                } else {
+                    if !current.is_empty() {
+                        current.push(Span::raw(" "));
+                    }
+                    current.push(Span::styled(t.to_string(), combined_style(&modifiers)));
+                }
+            }
+            MdEvent::Start(Tag::Paragraph) | MdEvent::End(TagEnd::Paragraph) => {
+                flush(&mut lines, &mut current, &mut first_line, in_item, &heading_marker);
+            }
+            _ => {}
+        }
+    }
+    flush(&mut lines, &mut current, &mut first_line, in_item, &heading_marker);
+    lines
+}
+
+/// Remove the most recently pushed occurrence of `modifier` from the
+/// stack, matching whichever `Start` pushed it (handles the non-nested
+/// case cleanly and degrades gracefully on malformed/unbalanced input).
+fn pop_modifier(stack: &mut Vec<Modifier>, modifier: Modifier) {
+    if let Some(pos) = stack.iter().rposition(|m| *m == modifier) {
+        stack.remove(pos);
+    }
+}
+
+fn combined_style(stack: &[Modifier]) -> Style {
+    stack
+        .iter()
+        .fold(Style::default(), |style, m| style.add_modifier(*m))
+}
@@ -0,0 +1,139 @@
+use std::path::PathBuf;
+
+use rusqlite::Connection;
+
+/// A single persisted chat message.
+pub struct StoredMessage {
+    pub speaker: String,
+    pub content: String,
+}
+
+/// A persisted snapshot of a `ChatSession`, decoupled from the TUI's own
+/// session type so this module doesn't need to know about `AppState`.
+pub struct StoredSession {
+    pub title: String,
+    pub conversation_id: Option<String>,
+    pub last_sse_id: Option<String>,
+    pub messages: Vec<StoredMessage>,
+}
+
+/// SQLite-backed store for session history, so tabs and scrollback survive
+/// restarts.
+pub struct SessionStore {
+    conn: Connection,
+}
+
+impl SessionStore {
+    /// Open (creating if necessary) the store at the standard data path.
+    pub fn open() -> Result<Self, String> {
+        let path = data_path().ok_or_else(|| "could not resolve data dir".to_string())?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+        }
+        let conn = Connection::open(&path).map_err(|err| err.to_string())?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS sessions (
+                position INTEGER PRIMARY KEY,
+                title TEXT NOT NULL,
+                conversation_id TEXT,
+                last_sse_id TEXT
+            );
+            CREATE TABLE IF NOT EXISTS messages (
+                session_position INTEGER NOT NULL,
+                idx INTEGER NOT NULL,
+                speaker TEXT NOT NULL,
+                content TEXT NOT NULL,
+                PRIMARY KEY (session_position, idx)
+            );",
+        )
+        .map_err(|err| err.to_string())?;
+        Ok(Self { conn })
+    }
+
+    /// Load all persisted sessions in tab order.
+    pub fn load_all(&self) -> Result<Vec<StoredSession>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT position, title, conversation_id, last_sse_id FROM sessions ORDER BY position",
+            )
+            .map_err(|err| err.to_string())?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                ))
+            })
+            .map_err(|err| err.to_string())?;
+
+        let mut sessions = Vec::new();
+        for row in rows {
+            let (position, title, conversation_id, last_sse_id) =
+                row.map_err(|err| err.to_string())?;
+            let messages = self.load_messages(position)?;
+            sessions.push(StoredSession {
+                title,
+                conversation_id,
+                last_sse_id,
+                messages,
+            });
+        }
+        Ok(sessions)
+    }
+
+    fn load_messages(&self, session_position: i64) -> Result<Vec<StoredMessage>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT speaker, content FROM messages WHERE session_position = ?1 ORDER BY idx")
+            .map_err(|err| err.to_string())?;
+        let rows = stmt
+            .query_map([session_position], |row| {
+                Ok(StoredMessage {
+                    speaker: row.get(0)?,
+                    content: row.get(1)?,
+                })
+            })
+            .map_err(|err| err.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|err| err.to_string())
+    }
+
+    /// Replace all persisted state with the given sessions, in tab order.
+    pub fn replace_all(&mut self, sessions: &[StoredSession]) -> Result<(), String> {
+        let tx = self.conn.transaction().map_err(|err| err.to_string())?;
+        tx.execute("DELETE FROM messages", [])
+            .map_err(|err| err.to_string())?;
+        tx.execute("DELETE FROM sessions", [])
+            .map_err(|err| err.to_string())?;
+        for (position, session) in sessions.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO sessions (position, title, conversation_id, last_sse_id) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![
+                    position as i64,
+                    session.title,
+                    session.conversation_id,
+                    session.last_sse_id,
+                ],
+            )
+            .map_err(|err| err.to_string())?;
+            for (idx, msg) in session.messages.iter().enumerate() {
+                tx.execute(
+                    "INSERT INTO messages (session_position, idx, speaker, content) VALUES (?1, ?2, ?3, ?4)",
+                    rusqlite::params![position as i64, idx as i64, msg.speaker, msg.content],
+                )
+                .map_err(|err| err.to_string())?;
+            }
+        }
+        tx.commit().map_err(|err| err.to_string())
+    }
+}
+
+fn data_path() -> Option<PathBuf> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))?;
+    Some(data_home.join("magent2").join("sessions.db"))
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use tiktoken_rs::CoreBPE;
+
+/// Local context-size meter, gated by the `MAGENT2_CONTEXT_METER` env var.
+///
+/// Tokenizes the chat history with a real BPE encoding (the same family
+/// used by `tiktoken-rs`) so users can see how close they are to the
+/// model's context limit before the gateway truncates or errors. Per-message
+/// token counts are cached by message index so re-rendering a frame only
+/// tokenizes the text newly appended since the last count, instead of the
+/// whole message every time (and without an unbounded cache of every
+/// intermediate prefix seen while a message streams in).
+pub struct TokenMeter {
+    encoding_name: String,
+    bpe: CoreBPE,
+    limit: usize,
+    cache: HashMap<usize, (String, usize)>,
+}
+
+const DEFAULT_ENCODING: &str = "cl100k_base";
+const DEFAULT_LIMIT: usize = 128_000;
+
+impl TokenMeter {
+    /// Build a meter from `MAGENT2_CONTEXT_METER` (enables it),
+    /// `MAGENT2_CONTEXT_ENCODING` (defaults to `cl100k_base`), and
+    /// `MAGENT2_CONTEXT_LIMIT` (defaults to 128000 tokens). Returns `None`
+    /// when disabled or the encoding name isn't recognized.
+    pub fn from_env() -> Option<Self> {
+        let enabled = std::env::var("MAGENT2_CONTEXT_METER")
+            .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+            .unwrap_or(false);
+        if !enabled {
+            return None;
+        }
+        let encoding_name =
+            std::env::var("MAGENT2_CONTEXT_ENCODING").unwrap_or_else(|_| DEFAULT_ENCODING.to_string());
+        let limit = std::env::var("MAGENT2_CONTEXT_LIMIT")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_LIMIT);
+        let bpe = load_encoding(&encoding_name)?;
+        Some(Self {
+            encoding_name,
+            bpe,
+            limit,
+            cache: HashMap::new(),
+        })
+    }
+
+    pub fn encoding_name(&self) -> &str {
+        &self.encoding_name
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Token count for the message at `idx`, tokenizing only the text
+    /// appended since the last call for that index (the common case while a
+    /// message streams in) and falling back to a full re-tokenize if the
+    /// content isn't a simple extension of what was cached (e.g. edited or
+    /// replaced outright).
+    pub fn count(&mut self, idx: usize, content: &str) -> usize {
+        if let Some((cached_content, cached_count)) = self.cache.get(&idx) {
+            if content == cached_content.as_str() {
+                return *cached_count;
+            }
+            if let Some(delta) = content.strip_prefix(cached_content.as_str()) {
+                let added = self.bpe.encode_with_special_tokens(delta).len();
+                let n = cached_count + added;
+                self.cache.insert(idx, (content.to_string(), n));
+                return n;
+            }
+        }
+        let n = self.bpe.encode_with_special_tokens(content).len();
+        self.cache.insert(idx, (content.to_string(), n));
+        n
+    }
+
+    /// Total token count across `messages`, summing each message's cached
+    /// (or freshly tokenized) count.
+    pub fn total<'a>(&mut self, messages: impl IntoIterator<Item = &'a str>) -> usize {
+        messages
+            .into_iter()
+            .enumerate()
+            .map(|(idx, m)| self.count(idx, m))
+            .sum()
+    }
+
+    /// Percentage of `limit` that `total_tokens` represents, clamped to 999
+    /// so a runaway conversation doesn't blow out the title bar's width.
+    pub fn percent_of_limit(&self, total_tokens: usize) -> u64 {
+        if self.limit == 0 {
+            return 0;
+        }
+        ((total_tokens as u64 * 100) / self.limit as u64).min(999)
+    }
+}
+
+fn load_encoding(name: &str) -> Option<CoreBPE> {
+    match name {
+        "cl100k_base" => tiktoken_rs::cl100k_base().ok(),
+        "o200k_base" => tiktoken_rs::o200k_base().ok(),
+        "p50k_base" => tiktoken_rs::p50k_base().ok(),
+        "r50k_base" | "gpt2" => tiktoken_rs::r50k_base().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a meter directly, bypassing `from_env`'s env-var gate.
+    fn test_meter(limit: usize) -> TokenMeter {
+        TokenMeter {
+            encoding_name: DEFAULT_ENCODING.to_string(),
+            bpe: load_encoding(DEFAULT_ENCODING).expect("cl100k_base encoding"),
+            limit,
+            cache: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_count_caches_by_message_index_not_content() {
+        let mut meter = test_meter(DEFAULT_LIMIT);
+        let n = meter.count(0, "hello world");
+        assert_eq!(meter.count(0, "hello world"), n);
+        assert_eq!(meter.cache.len(), 1);
+    }
+
+    #[test]
+    fn test_count_extends_via_delta_when_content_grows() {
+        let mut meter = test_meter(DEFAULT_LIMIT);
+        let first = meter.count(0, "hello");
+        let grown = meter.count(0, "hello world");
+        let direct = meter.bpe.encode_with_special_tokens("hello world").len();
+        assert_eq!(grown, direct);
+        assert!(grown >= first);
+    }
+
+    #[test]
+    fn test_count_retokenizes_fully_when_content_is_not_an_extension() {
+        let mut meter = test_meter(DEFAULT_LIMIT);
+        meter.count(0, "hello world");
+        let replaced = meter.count(0, "goodbye");
+        let direct = meter.bpe.encode_with_special_tokens("goodbye").len();
+        assert_eq!(replaced, direct);
+    }
+
+    #[test]
+    fn test_total_sums_per_message_counts_and_keeps_cache_bounded_by_index() {
+        let mut meter = test_meter(DEFAULT_LIMIT);
+        let total = meter.total(["hello", "world"]);
+        let a = meter.bpe.encode_with_special_tokens("hello").len();
+        let b = meter.bpe.encode_with_special_tokens("world").len();
+        assert_eq!(total, a + b);
+        assert_eq!(meter.cache.len(), 2);
+    }
+
+    #[test]
+    fn test_percent_of_limit_clamps_to_999() {
+        let meter = test_meter(100);
+        assert_eq!(meter.percent_of_limit(50), 50);
+        assert_eq!(meter.percent_of_limit(10_000), 999);
+    }
+
+    #[test]
+    fn test_percent_of_limit_zero_limit_is_zero() {
+        let meter = test_meter(0);
+        assert_eq!(meter.percent_of_limit(1), 0);
+    }
+}
@@ -0,0 +1,57 @@
+use reqwest::Client;
+use serde::Serialize;
+
+const SUMMARY_CHARS: usize = 500;
+
+/// A single run-lifecycle event forwarded to the configured webhook URL.
+#[derive(Serialize)]
+pub struct WebhookEvent {
+    pub conversation_id: Option<String>,
+    pub session_title: String,
+    pub event: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+/// Outbound webhook client for forwarding run lifecycle events (run
+/// started, tool step succeeded/failed, stream closed, stream error) to a
+/// user-configured URL, gated by the `MAGENT2_WEBHOOK_URL` env var.
+pub struct Webhook {
+    url: Option<String>,
+}
+
+impl Webhook {
+    pub fn from_env() -> Self {
+        let url = std::env::var("MAGENT2_WEBHOOK_URL")
+            .ok()
+            .filter(|u| !u.is_empty());
+        Self { url }
+    }
+
+    /// Fire-and-forget POST of `event`; a no-op if no webhook URL is
+    /// configured. Spawned so a slow or unreachable endpoint never blocks
+    /// the render loop; failures are silently ignored.
+    pub fn send(&self, client: &Client, mut event: WebhookEvent) {
+        let Some(url) = self.url.clone() else {
+            return;
+        };
+        event.summary = event.summary.map(|s| truncate_snippet(&s));
+        let client = client.clone();
+        tokio::spawn(async move {
+            let _ = client.post(&url).json(&event).send().await;
+        });
+    }
+}
+
+fn truncate_snippet(text: &str) -> String {
+    let trimmed = text.trim();
+    let mut out: String = trimmed.chars().take(SUMMARY_CHARS).collect();
+    if trimmed.chars().count() > SUMMARY_CHARS {
+        out.push('…');
+    }
+    out
+}